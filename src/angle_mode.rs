@@ -0,0 +1,76 @@
+use std::fmt::Display;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::CONFIG;
+
+/// Unit that trigonometric functions take and return angles in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AngleMode {
+    #[serde(rename = "deg")]
+    Degrees,
+    #[default]
+    #[serde(rename = "rad")]
+    Radians,
+    #[serde(rename = "grad")]
+    Gradians,
+}
+
+impl Display for AngleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AngleMode::Degrees => "deg",
+            AngleMode::Radians => "rad",
+            AngleMode::Gradians => "grad",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl AngleMode {
+    /// Parses a mode from its short or long name, like `deg` or `degrees`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "deg" | "degree" | "degrees" | "°" => Some(AngleMode::Degrees),
+            "rad" | "radian" | "radians" => Some(AngleMode::Radians),
+            "grad" | "gradian" | "gradians" | "gon" => Some(AngleMode::Gradians),
+            _ => None,
+        }
+    }
+
+    /// Mode currently set in [CONFIG]
+    pub fn current() -> Self {
+        let config = CONFIG.lock().unwrap();
+        config
+            .as_ref()
+            .map(|config| config.angle_mode)
+            .unwrap_or_default()
+    }
+
+    /// Size of a full circle in this unit
+    fn full_turn(&self) -> f64 {
+        match self {
+            AngleMode::Degrees => 360.0,
+            AngleMode::Radians => std::f64::consts::TAU,
+            AngleMode::Gradians => 400.0,
+        }
+    }
+
+    /// Converts an angle in this unit to `target`
+    pub fn convert(&self, angle: f64, target: AngleMode) -> f64 {
+        if *self == target {
+            return angle;
+        }
+
+        angle / self.full_turn() * target.full_turn()
+    }
+
+    pub fn to_radians(&self, angle: f64) -> f64 {
+        self.convert(angle, AngleMode::Radians)
+    }
+
+    pub fn from_radians(&self, angle: f64) -> f64 {
+        AngleMode::Radians.convert(angle, *self)
+    }
+}
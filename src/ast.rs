@@ -0,0 +1,527 @@
+//! Builds a syntax tree out of [tokens](crate::lexer::Token)
+use std::ops::Range;
+
+use num_rational::BigRational;
+
+use crate::{
+    angle_mode::AngleMode,
+    calc_error::CalcError,
+    lexer::{Operator, Token, TokenKind},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Number(BigRational),
+    /// An angle in a specific unit, which is converted to the current [AngleMode] when evaluated
+    Angle(BigRational, AngleMode),
+    /// An imaginary literal like `4i`, holding the imaginary part
+    Imaginary(BigRational),
+    Variable {
+        name: String,
+        span: Range<usize>,
+    },
+    /// Result of the line with the given number, written as `[n]`
+    LineReference {
+        index: usize,
+        span: Range<usize>,
+    },
+    /// Result of the last calculation, implied when an expression starts with an operator
+    ///
+    /// The span points at that operator
+    Continuation {
+        span: Range<usize>,
+    },
+    Negate(Box<Node>),
+    /// Factorial of the operand, the span points at the `!`
+    Factorial {
+        operand: Box<Node>,
+        span: Range<usize>,
+    },
+    /// A function call like `f(1, 2)`, the span covers the whole call
+    Call {
+        name: String,
+        arguments: Vec<Node>,
+        span: Range<usize>,
+    },
+    BinaryOperation {
+        operator: Operator,
+        /// Span of the operator itself
+        span: Range<usize>,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+    /// A number written directly before a unit, like `5 km` or `3 m^2`, which multiplies them
+    Quantity {
+        number: Box<Node>,
+        unit: Box<Node>,
+        /// Span of the unit
+        span: Range<usize>,
+    },
+    /// Shows a value in another unit, like `5 km to m`
+    Conversion {
+        value: Box<Node>,
+        unit: Box<Node>,
+        /// The unit as it was written, used to show the result
+        name: String,
+        /// Span of the unit
+        span: Range<usize>,
+    },
+}
+
+/// Words that convert the value before them to the unit after them
+pub const CONVERSION_KEYWORDS: &[&str] = &["to", "in"];
+
+/// Whether the token at `position` is a keyword like `to`, rather than a name
+///
+/// `in` is also inches, so it's only a keyword when a unit follows it, like `5 mi in km` but not `5 in` or `5 in to cm`
+fn is_conversion_keyword(tokens: &[Token], position: usize) -> bool {
+    let is_keyword = |token: Option<&Token>| matches!(token.map(|t| &t.kind), Some(TokenKind::Identifier(name)) if CONVERSION_KEYWORDS.contains(&name.as_str()));
+
+    match tokens.get(position).map(|t| &t.kind) {
+        Some(TokenKind::Identifier(name)) if name == "in" => {
+            let next = tokens.get(position + 1);
+            matches!(
+                next.map(|t| &t.kind),
+                Some(TokenKind::Identifier(_) | TokenKind::LeftParen | TokenKind::Number(_))
+            ) && !is_keyword(next)
+        }
+        _ => is_keyword(tokens.get(position)),
+    }
+}
+
+/// Whether the token at `position` is a unit after a number, like the `km` in `5 km`, rather than a call or a conversion keyword
+fn is_unit(tokens: &[Token], position: usize) -> bool {
+    let is_call = matches!(
+        tokens.get(position + 1).map(|t| &t.kind),
+        Some(TokenKind::LeftParen)
+    );
+
+    matches!(
+        tokens.get(position).map(|t| &t.kind),
+        Some(TokenKind::Identifier(_))
+    ) && !is_call
+        && !is_conversion_keyword(tokens, position)
+}
+
+/// Whether the tokens at `position` are a minus sign before a number with a unit, like `-40 °C`
+fn is_negative_quantity(tokens: &[Token], position: usize) -> bool {
+    matches!(
+        tokens.get(position).map(|t| &t.kind),
+        Some(TokenKind::Operator(Operator::Subtract))
+    ) && matches!(
+        tokens.get(position + 1).map(|t| &t.kind),
+        Some(TokenKind::Number(_))
+    ) && is_unit(tokens, position + 2)
+}
+
+/// Recursive descent parser turning tokens into a [Node]
+struct AstBuilder<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+/// Parses a list of tokens into a syntax tree
+///
+/// If the expression starts with a binary operator, the missing first operand becomes a [continuation](Node::Continuation).
+/// A minus sign written right against a number with a unit isn't one, so `-40 °C` is a temperature rather than 40 °C less than the last result
+pub fn build(tokens: &[Token]) -> Result<Node, CalcError> {
+    if tokens.is_empty() {
+        return Err(CalcError::EmptyExpression);
+    }
+
+    let mut builder = AstBuilder {
+        tokens,
+        position: 0,
+    };
+
+    let is_negative_number =
+        is_negative_quantity(tokens, 0) && tokens[0].span.end == tokens[1].span.start;
+
+    let node = if matches!(tokens[0].kind, TokenKind::Operator(_)) && !is_negative_number {
+        let continuation = Node::Continuation {
+            span: tokens[0].span.clone(),
+        };
+        builder.binary_operation(continuation, 0)?
+    } else {
+        builder.expression(0)?
+    };
+
+    if let Some(token) = builder.peek() {
+        let span = token.span.clone();
+        return Err(match token.kind {
+            TokenKind::RightParen => CalcError::UnmatchedClosingParenthesis { span },
+            _ => CalcError::MissingOperator { span },
+        });
+    }
+
+    Ok(node)
+}
+
+impl AstBuilder<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    /// Points just past the last token, for errors about the expression ending too early
+    fn end_span(&self) -> Range<usize> {
+        let end = self.tokens.last().map_or(0, |t| t.span.end);
+        end..end + 1
+    }
+
+    /// Parses operators by precedence climbing, only consuming operators that bind at least as tight as `min_precedence`
+    fn expression(&mut self, min_precedence: u8) -> Result<Node, CalcError> {
+        let left = self.unary()?;
+        self.binary_operation(left, min_precedence)
+    }
+
+    fn binary_operation(&mut self, mut left: Node, min_precedence: u8) -> Result<Node, CalcError> {
+        loop {
+            // Conversions bind looser than any operator, so `1 + 2 km to m` converts `1 + 2 km`
+            if min_precedence == 0 && is_conversion_keyword(self.tokens, self.position) {
+                left = self.conversion(left)?;
+                continue;
+            }
+
+            let Some(Token {
+                kind: TokenKind::Operator(operator),
+                span,
+            }) = self.peek()
+            else {
+                break;
+            };
+
+            let operator = *operator;
+            let span = span.clone();
+            if operator.precedence() < min_precedence {
+                break;
+            }
+
+            self.next();
+
+            let next_precedence = if operator.is_right_associative() {
+                operator.precedence()
+            } else {
+                operator.precedence() + 1
+            };
+
+            let right = self.expression(next_precedence)?;
+            left = Node::BinaryOperation {
+                operator,
+                span,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    /// Unary minus binds looser than `^`, so `-2^2` is `-(2^2)`
+    ///
+    /// Before a number with a unit it's part of the number instead, so `-40 °C` is a temperature below zero rather than 40 °C negated
+    fn unary(&mut self) -> Result<Node, CalcError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Operator(Operator::Subtract))
+                if is_negative_quantity(self.tokens, self.position) =>
+            {
+                self.next();
+                let number = self.primary()?;
+                self.quantity(Node::Negate(Box::new(number)))
+            }
+            Some(TokenKind::Operator(Operator::Subtract)) => {
+                self.next();
+                let operand = self.expression(Operator::Power.precedence())?;
+                Ok(Node::Negate(Box::new(operand)))
+            }
+            Some(TokenKind::Operator(Operator::Add)) => {
+                self.next();
+                self.expression(Operator::Power.precedence())
+            }
+            _ => self.postfix(),
+        }
+    }
+
+    /// Parses the unit after a conversion keyword, the keyword being the next token
+    fn conversion(&mut self, value: Node) -> Result<Node, CalcError> {
+        self.next();
+        let start = self.position;
+        let unit = self.expression(1)?;
+
+        let tokens = &self.tokens[start..self.position];
+        let name = tokens.iter().map(|token| token.kind.to_string()).collect();
+        let span = tokens[0].span.start..tokens[tokens.len() - 1].span.end;
+
+        Ok(Node::Conversion {
+            value: Box::new(value),
+            unit: Box::new(unit),
+            name,
+            span,
+        })
+    }
+
+    /// Factorials bind tighter than anything else, so `2^3!` is `2^(3!)` and `-3!` is `-(3!)`
+    ///
+    /// A number or parenthesised expression directly followed by a name is multiplied by it, so units can be written like `5 km`, `3 m^2`, or `(-40) °C`
+    fn postfix(&mut self) -> Result<Node, CalcError> {
+        let takes_unit = matches!(
+            self.peek().map(|t| &t.kind),
+            Some(TokenKind::Number(_) | TokenKind::LeftParen)
+        );
+        let mut node = self.primary()?;
+
+        if takes_unit && is_unit(self.tokens, self.position) {
+            node = self.quantity(node)?;
+        }
+
+        while let Some(Token {
+            kind: TokenKind::Factorial,
+            span,
+        }) = self.peek()
+        {
+            node = Node::Factorial {
+                operand: Box::new(node),
+                span: span.clone(),
+            };
+            self.next();
+        }
+
+        Ok(node)
+    }
+
+    /// Parses the unit after `number`, the unit being the next token
+    fn quantity(&mut self, number: Node) -> Result<Node, CalcError> {
+        let start = self.position;
+        let unit = self.expression(Operator::Power.precedence())?;
+        let span = self.tokens[start].span.start..self.tokens[self.position - 1].span.end;
+
+        Ok(Node::Quantity {
+            number: Box::new(number),
+            unit: Box::new(unit),
+            span,
+        })
+    }
+
+    fn primary(&mut self) -> Result<Node, CalcError> {
+        let end_span = self.end_span();
+        let Some(token) = self.next() else {
+            return Err(CalcError::MissingOperand { span: end_span });
+        };
+
+        let span = token.span.clone();
+        match &token.kind {
+            TokenKind::Number(value) => Ok(Node::Number(value.clone())),
+            TokenKind::Angle(value, unit) => Ok(Node::Angle(value.clone(), *unit)),
+            TokenKind::Imaginary(value) => Ok(Node::Imaginary(value.clone())),
+            TokenKind::Identifier(name) => {
+                let name = name.clone();
+                match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::LeftParen,
+                        ..
+                    }) => self.call(name, span),
+                    _ => Ok(Node::Variable { name, span }),
+                }
+            }
+            TokenKind::LineReference(index) => Ok(Node::LineReference {
+                index: *index,
+                span,
+            }),
+            TokenKind::LeftParen => {
+                let inner = self.expression(0)?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RightParen,
+                        ..
+                    }) => Ok(inner),
+                    Some(token) => Err(CalcError::MissingOperator {
+                        span: token.span.clone(),
+                    }),
+                    None => Err(CalcError::UnclosedParenthesis { span }),
+                }
+            }
+            TokenKind::RightParen => Err(CalcError::UnmatchedClosingParenthesis { span }),
+            TokenKind::Operator(_) | TokenKind::Comma | TokenKind::Factorial => {
+                Err(CalcError::MissingOperand { span })
+            }
+        }
+    }
+
+    /// Parses the argument list of a call, `name_span` being the span of the function's name
+    fn call(&mut self, name: String, name_span: Range<usize>) -> Result<Node, CalcError> {
+        let opening_span = self.next().unwrap().span.clone();
+        let mut arguments = Vec::new();
+
+        if let Some(Token {
+            kind: TokenKind::RightParen,
+            span,
+        }) = self.peek()
+        {
+            let span = name_span.start..span.end;
+            self.next();
+            return Ok(Node::Call {
+                name,
+                arguments,
+                span,
+            });
+        }
+
+        loop {
+            arguments.push(self.expression(0)?);
+
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Comma,
+                    ..
+                }) => continue,
+                Some(Token {
+                    kind: TokenKind::RightParen,
+                    span,
+                }) => {
+                    let span = name_span.start..span.end;
+                    return Ok(Node::Call {
+                        name,
+                        arguments,
+                        span,
+                    });
+                }
+                Some(token) => {
+                    return Err(CalcError::MissingOperator {
+                        span: token.span.clone(),
+                    })
+                }
+                None => return Err(CalcError::UnclosedParenthesis { span: opening_span }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::tokenize, number::Number};
+
+    /// The tree for `expression` written out with every operation in parentheses, like `(+ 1 (* 2 3))`
+    fn tree(expression: &str) -> String {
+        write(&build(&tokenize(expression).unwrap()).unwrap())
+    }
+
+    fn error(expression: &str) -> CalcError {
+        build(&tokenize(expression).unwrap()).unwrap_err()
+    }
+
+    fn write(node: &Node) -> String {
+        let number = |value: &BigRational| Number::from_rational(value.clone()).to_string();
+        match node {
+            Node::Number(value) => number(value),
+            Node::Angle(value, unit) => format!("{}{unit}", number(value)),
+            Node::Imaginary(value) => format!("{}i", number(value)),
+            Node::Variable { name, .. } => name.clone(),
+            Node::LineReference { index, .. } => format!("[{index}]"),
+            Node::Continuation { .. } => "ans".to_owned(),
+            Node::Negate(operand) => format!("(- {})", write(operand)),
+            Node::Factorial { operand, .. } => format!("(! {})", write(operand)),
+            Node::Call {
+                name, arguments, ..
+            } => {
+                let arguments: Vec<String> = arguments.iter().map(write).collect();
+                format!("({name} {})", arguments.join(" "))
+            }
+            Node::BinaryOperation {
+                operator,
+                left,
+                right,
+                ..
+            } => format!("({operator} {} {})", write(left), write(right)),
+            Node::Quantity { number, unit, .. } => {
+                format!("(quantity {} {})", write(number), write(unit))
+            }
+            Node::Conversion { value, unit, .. } => {
+                format!("(to {} {})", write(value), write(unit))
+            }
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(tree("1 + 2 * 3"), "(+ 1 (* 2 3))");
+        assert_eq!(tree("(1 + 2) * 3"), "(* (+ 1 2) 3)");
+        assert_eq!(tree("8 / 4 / 2"), "(/ (/ 8 4) 2)");
+        assert_eq!(tree("2^3^2"), "(^ 2 (^ 3 2))");
+        assert_eq!(tree("2^3!"), "(^ 2 (! 3))");
+        assert_eq!(tree("7 % 3 + 1"), "(+ (% 7 3) 1)");
+    }
+
+    #[test]
+    fn unary_minus_binds_looser_than_power() {
+        assert_eq!(tree("(-2^2)"), "(- (^ 2 2))");
+        assert_eq!(tree("2^-1"), "(^ 2 (- 1))");
+        assert_eq!(tree("(-3!)"), "(- (! 3))");
+    }
+
+    #[test]
+    fn continuations() {
+        assert_eq!(tree("+ 1"), "(+ ans 1)");
+        assert_eq!(tree("* 2 + 1"), "(+ (* ans 2) 1)");
+        assert_eq!(tree("-2^2"), "(- ans (^ 2 2))");
+        assert_eq!(tree("- 40 °C"), "(- ans (quantity 40 °C))");
+    }
+
+    #[test]
+    fn negative_quantities() {
+        assert_eq!(tree("-40 °C"), "(quantity (- 40) °C)");
+        assert_eq!(tree("-40 °C to °F"), "(to (quantity (- 40) °C) °F)");
+        assert_eq!(tree("1 + -5 m"), "(+ 1 (quantity (- 5) m))");
+        assert_eq!(tree("(-40) °C"), "(quantity (- 40) °C)");
+    }
+
+    #[test]
+    fn units_and_conversions() {
+        assert_eq!(tree("5 km"), "(quantity 5 km)");
+        assert_eq!(tree("3 m^2"), "(quantity 3 (^ m 2))");
+        assert_eq!(tree("1 + 2 km to m"), "(to (+ 1 (quantity 2 km)) m)");
+        assert_eq!(tree("5 mi in km"), "(to (quantity 5 mi) km)");
+        assert_eq!(tree("5 in"), "(quantity 5 in)");
+        assert_eq!(tree("30 °"), "(quantity 30 °)");
+    }
+
+    #[test]
+    fn calls() {
+        assert_eq!(tree("max(1, 2 + 3)"), "(max 1 (+ 2 3))");
+        assert_eq!(tree("f()"), "(f )");
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        assert_eq!(build(&[]).unwrap_err(), CalcError::EmptyExpression);
+
+        let err = error("(1 + 2");
+        assert_eq!(err.code(), "E004");
+        assert_eq!(err.span(), Some(0..1));
+
+        let err = error("1 + 2)");
+        assert_eq!(err.code(), "E005");
+        assert_eq!(err.span(), Some(5..6));
+
+        let err = error("2 sqrt(4)");
+        assert_eq!(err.code(), "E006");
+        assert_eq!(err.span(), Some(2..6));
+
+        let err = error("2 3");
+        assert_eq!(err.code(), "E006");
+        assert_eq!(err.span(), Some(2..3));
+
+        let err = error("1 +");
+        assert_eq!(err.code(), "E007");
+        assert_eq!(err.span(), Some(3..4));
+
+        let err = error("max(1, 2");
+        assert_eq!(err.code(), "E004");
+        assert_eq!(err.span(), Some(3..4));
+    }
+}
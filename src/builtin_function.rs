@@ -0,0 +1,80 @@
+use crate::{
+    calc_error::CalcError, number::Number, parameter_documentation::ParameterDocumentation,
+    FUNCTIONS,
+};
+
+/// What a built-in function does with arguments that have units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnitHandling {
+    /// Arguments can't have units
+    Unitless,
+    /// Arguments all have to measure the same thing, and the result has their unit, like `max(1 m, 50 cm)`
+    Same,
+    /// The result's unit is the nth root of the argument's, like `sqrt(4 m^2)` being `2 m`
+    Root(i8),
+}
+
+/// A function that's always available, like `sqrt`
+#[derive(Clone)]
+pub struct BuiltinFunction {
+    pub name: &'static str,
+    pub help_text: Option<&'static str>,
+    /// Only ever called with a number of arguments within `min_arguments..=max_arguments`
+    ///
+    /// Errors are pointed at the call, so their spans don't matter
+    pub action: fn(&[Number]) -> Result<Number, CalcError>,
+    pub aliases: Option<Vec<&'static str>>,
+    pub min_arguments: usize,
+    /// [None] if the function takes any number of arguments
+    pub max_arguments: Option<usize>,
+    pub units: UnitHandling,
+    pub parameter_documentation: Option<ParameterDocumentation>,
+}
+
+impl BuiltinFunction {
+    /// Looks up a registered function by name or alias
+    pub fn find(name: &str) -> Option<BuiltinFunction> {
+        let functions = FUNCTIONS.lock().unwrap();
+        functions
+            .values()
+            .find(|function| {
+                function.name == name
+                    || function
+                        .aliases
+                        .as_ref()
+                        .is_some_and(|aliases| aliases.contains(&name))
+            })
+            .cloned()
+    }
+
+    pub fn accepts(&self, argument_count: usize) -> bool {
+        argument_count >= self.min_arguments
+            && self.max_arguments.is_none_or(|max| argument_count <= max)
+    }
+
+    /// Human-readable number of arguments the function takes, like "1 to 2"
+    pub fn arity(&self) -> String {
+        match self.max_arguments {
+            Some(max) if max == self.min_arguments => max.to_string(),
+            Some(max) => format!("{} to {max}", self.min_arguments),
+            None => format!("at least {}", self.min_arguments),
+        }
+    }
+
+    /// Signature of the function, like `log(x, base)`
+    pub fn signature(&self) -> String {
+        let parameters = self
+            .parameter_documentation
+            .clone()
+            .map(|parameters| {
+                parameters
+                    .into_iter()
+                    .map(|(name, _, _)| name)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+
+        format!("{}({parameters})", self.name)
+    }
+}
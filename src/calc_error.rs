@@ -0,0 +1,306 @@
+use std::{fmt::Display, ops::Range};
+
+use inline_colorization::*;
+
+use crate::{number::MAX_FACTORIAL, variable::VariableKind};
+
+/// Everything that can go wrong while parsing or calculating an expression, or running a command
+///
+/// Most variants carry the byte range of the offending part of the expression so it can be [underlined](CalcError::underline).
+/// Every variant also has a stable [code](CalcError::code) that can be looked up with `help <code>`
+#[derive(Clone, Debug, PartialEq)]
+pub enum CalcError {
+    EmptyExpression,
+    InvalidNumber {
+        text: String,
+        span: Range<usize>,
+    },
+    UnexpectedCharacter {
+        character: char,
+        span: Range<usize>,
+    },
+    UnclosedParenthesis {
+        span: Range<usize>,
+    },
+    UnmatchedClosingParenthesis {
+        span: Range<usize>,
+    },
+    MissingOperator {
+        span: Range<usize>,
+    },
+    MissingOperand {
+        span: Range<usize>,
+    },
+    UnknownVariable {
+        name: String,
+        span: Range<usize>,
+    },
+    BadLineReference {
+        message: String,
+        span: Range<usize>,
+    },
+    NoContinuation {
+        span: Range<usize>,
+    },
+    DivisionByZero {
+        span: Range<usize>,
+    },
+    TooManyEquals,
+    InvalidVariableName {
+        name: String,
+        span: Range<usize>,
+    },
+    CommandArgument {
+        command: String,
+        message: String,
+    },
+    UnknownFunction {
+        name: String,
+        span: Range<usize>,
+    },
+    WrongArgumentCount {
+        name: String,
+        expected: String,
+        found: usize,
+        span: Range<usize>,
+    },
+    RecursionLimit {
+        name: String,
+        span: Range<usize>,
+    },
+    InvalidFactorial {
+        span: Range<usize>,
+    },
+    /// Two values that have to measure the same thing don't, like metres and seconds
+    IncompatibleUnits {
+        left: String,
+        right: String,
+        span: Range<usize>,
+    },
+    InvalidUnit {
+        message: String,
+        span: Range<usize>,
+    },
+    /// A declaration tried to replace a built-in constant or `ans`
+    ReadOnlyVariable {
+        name: String,
+        kind: VariableKind,
+        span: Range<usize>,
+    },
+    /// An exact power would be too big to calculate, like `2^1000000`
+    TooLargeToCalculate {
+        span: Range<usize>,
+    },
+    /// A built-in function was given a number it can't work with, like a negative exponent to `modpow`
+    InvalidArgument {
+        name: String,
+        message: String,
+        span: Range<usize>,
+    },
+}
+
+impl Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalcError::EmptyExpression => write!(f, "Expression is empty"),
+            CalcError::InvalidNumber { text, .. } => write!(f, "\"{text}\" is not a valid number"),
+            CalcError::UnexpectedCharacter { character, .. } => {
+                write!(f, "Unexpected character \"{character}\"")
+            }
+            CalcError::UnclosedParenthesis { .. } => write!(f, "Unmatched opening parenthesis"),
+            CalcError::UnmatchedClosingParenthesis { .. } => {
+                write!(f, "Unmatched closing parenthesis")
+            }
+            CalcError::MissingOperator { .. } => {
+                write!(f, "Expected an operator between operands")
+            }
+            CalcError::MissingOperand { .. } => write!(f, "Expected an operand"),
+            CalcError::UnknownVariable { name, .. } => {
+                write!(f, "Variable or unit \"{name}\" doesn't exist")
+            }
+            CalcError::BadLineReference { message, .. } => write!(f, "{message}"),
+            CalcError::NoContinuation { .. } => {
+                write!(f, "No expressions in history to use for continuation")
+            }
+            CalcError::DivisionByZero { .. } => write!(f, "Division by zero"),
+            CalcError::TooManyEquals => write!(f, "Too many equals signs"),
+            CalcError::InvalidVariableName { name, .. } => {
+                write!(f, "\"{name}\" is not a valid variable name")
+            }
+            CalcError::CommandArgument { command, message } => write!(f, "{command}: {message}"),
+            CalcError::UnknownFunction { name, .. } => {
+                write!(f, "Function \"{name}\" doesn't exist")
+            }
+            CalcError::WrongArgumentCount {
+                name,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "{name} takes {expected} argument(s) but was given {found}"
+            ),
+            CalcError::RecursionLimit { name, .. } => {
+                write!(f, "Calling {name} recursed too deeply")
+            }
+            CalcError::InvalidFactorial { .. } => write!(
+                f,
+                "Factorial is only defined for whole numbers from 0 to {MAX_FACTORIAL}"
+            ),
+            CalcError::IncompatibleUnits { left, right, .. } => {
+                write!(f, "Units {left} and {right} don't measure the same thing")
+            }
+            CalcError::InvalidUnit { message, .. } => write!(f, "{message}"),
+            CalcError::TooLargeToCalculate { .. } => {
+                write!(f, "Result is too large to calculate exactly")
+            }
+            CalcError::InvalidArgument { name, message, .. } => write!(f, "{name}: {message}"),
+            CalcError::ReadOnlyVariable { name, kind, .. } => match kind {
+                VariableKind::Result => write!(f, "\"{name}\" always holds the last result"),
+                _ => write!(
+                    f,
+                    "\"{name}\" is built in, put \"shadow\" before the declaration to replace it"
+                ),
+            },
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+impl CalcError {
+    /// Byte range of the part of the expression that caused the error, if it's known
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.clone().span_mut().cloned()
+    }
+
+    /// Moves the error's span `offset` bytes to the right, for errors from a smaller part of a larger expression
+    pub fn shifted(mut self, offset: usize) -> Self {
+        if let Some(span) = self.span_mut() {
+            *span = span.start + offset..span.end + offset;
+        }
+
+        self
+    }
+
+    /// Points the error at `span`, if it points at anything at all
+    pub fn at(mut self, span: Range<usize>) -> Self {
+        if let Some(own_span) = self.span_mut() {
+            *own_span = span;
+        }
+
+        self
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Range<usize>> {
+        match self {
+            CalcError::InvalidNumber { span, .. }
+            | CalcError::UnexpectedCharacter { span, .. }
+            | CalcError::UnclosedParenthesis { span }
+            | CalcError::UnmatchedClosingParenthesis { span }
+            | CalcError::MissingOperator { span }
+            | CalcError::MissingOperand { span }
+            | CalcError::UnknownVariable { span, .. }
+            | CalcError::BadLineReference { span, .. }
+            | CalcError::NoContinuation { span }
+            | CalcError::DivisionByZero { span }
+            | CalcError::InvalidVariableName { span, .. }
+            | CalcError::UnknownFunction { span, .. }
+            | CalcError::WrongArgumentCount { span, .. }
+            | CalcError::RecursionLimit { span, .. }
+            | CalcError::InvalidFactorial { span }
+            | CalcError::IncompatibleUnits { span, .. }
+            | CalcError::InvalidUnit { span, .. }
+            | CalcError::ReadOnlyVariable { span, .. }
+            | CalcError::TooLargeToCalculate { span }
+            | CalcError::InvalidArgument { span, .. } => Some(span),
+            CalcError::EmptyExpression
+            | CalcError::TooManyEquals
+            | CalcError::CommandArgument { .. } => None,
+        }
+    }
+
+    /// Stable code identifying the kind of error, e.g. `E008`
+    ///
+    /// Codes are never reused, even if the error they belong to is removed
+    pub fn code(&self) -> &'static str {
+        match self {
+            CalcError::EmptyExpression => "E001",
+            CalcError::InvalidNumber { .. } => "E002",
+            CalcError::UnexpectedCharacter { .. } => "E003",
+            CalcError::UnclosedParenthesis { .. } => "E004",
+            CalcError::UnmatchedClosingParenthesis { .. } => "E005",
+            CalcError::MissingOperator { .. } => "E006",
+            CalcError::MissingOperand { .. } => "E007",
+            CalcError::UnknownVariable { .. } => "E008",
+            CalcError::BadLineReference { .. } => "E009",
+            CalcError::NoContinuation { .. } => "E010",
+            CalcError::DivisionByZero { .. } => "E011",
+            CalcError::TooManyEquals => "E012",
+            // E013 was for non-numeric variable values, from before declarations accepted expressions
+            CalcError::CommandArgument { .. } => "E014",
+            CalcError::InvalidVariableName { .. } => "E015",
+            CalcError::UnknownFunction { .. } => "E016",
+            CalcError::WrongArgumentCount { .. } => "E017",
+            CalcError::RecursionLimit { .. } => "E018",
+            CalcError::InvalidFactorial { .. } => "E019",
+            CalcError::IncompatibleUnits { .. } => "E020",
+            CalcError::InvalidUnit { .. } => "E021",
+            CalcError::ReadOnlyVariable { .. } => "E022",
+            CalcError::TooLargeToCalculate { .. } => "E023",
+            CalcError::InvalidArgument { .. } => "E024",
+        }
+    }
+
+    /// Longer description of what an error code means and how to fix it
+    pub fn explain(code: &str) -> Option<&'static str> {
+        let explanation = match code.to_uppercase().as_str() {
+            "E001" => "The expression is empty, there's nothing to calculate.",
+            "E002" => "A number couldn't be read, usually because it has more than one decimal point, like \"1.2.3\".",
+            "E003" => "The expression contains a character SmartCalc doesn't understand. Supported operators are + - * / ^ and %.",
+            "E004" => "An opening parenthesis \"(\" is never closed. Add a \")\" after the expression it groups.",
+            "E005" => "A closing parenthesis \")\" doesn't have a matching \"(\" before it.",
+            "E006" => "Two operands are next to each other without an operator between them, like \"2 3\".",
+            "E007" => "An operator is missing its right-hand operand, like \"1 +\".",
+            "E008" => "The expression uses a variable or unit that doesn't exist. Declare a variable with \"name = value\", or run \"showvariables\" to see what exists.",
+            "E009" => "A line reference like \"[2]\" points at a line that doesn't exist or doesn't have a result. Line references start at 1 and only count calculations in the current history.",
+            "E010" => "The expression starts with an operator, which continues from the last result, but nothing has been calculated yet.",
+            "E011" => "The right-hand side of a division or modulo is zero, or the modulus given to \"modpow\" is.",
+            "E012" => "A variable declaration can only contain one \"=\", like \"x = 5\".",
+            "E014" => "A command was given an argument it can't use. Run \"help <command>\" to see what it expects.",
+            "E015" => "The left-hand side of a declaration has to be a single name made of letters, digits, and underscores that doesn't start with a digit, like \"x\" or \"total_2\".",
+            "E016" => "The expression calls a function that hasn't been defined. Define one with \"f(x) = expression\", or run \"showvariables\" to see what exists.",
+            "E017" => "A function was called with a different number of arguments than it has parameters.",
+            "E018" => "Function calls nested too deeply, usually because a function calls itself. Functions can't be recursive since there's no way for them to stop.",
+            "E019" => "\"!\" was used on a negative number, a number with a fractional part, or a number too large to calculate the factorial of.",
+            "E020" => "Two values with units that measure different things were added, subtracted, compared, or converted, like \"5 m + 2 s\" or \"5 kg to m\".",
+            "E021" => "A unit was used somewhere it can't be, like in an exponent (\"2^(3 m)\"), in a function that only takes plain numbers (\"sin(5 m)\"), or in a root that doesn't come out even (\"sqrt(5 m)\").",
+            "E022" => "Built-in constants like \"pi\" and \"c\", built-in functions like \"sqrt\", and \"ans\" (the last result), can't be assigned to with \"name = value\" or \"name(x) = ...\". Pick a different name, or run \"shadow name = value\" to replace one with your own until \"clearvariables\" is run.",
+            "E023" => "An exact power like \"2^1000000\" or \"(1/3)^1000000\" would have more than a million bits, which takes too long to calculate and show. Use a smaller power.",
+            "E024" => "A built-in function was given a number outside what it accepts, like a fraction or negative exponent to \"modpow\". Run \"help <function>\" to see what each argument can be.",
+            _ => return None,
+        };
+
+        Some(explanation)
+    }
+
+    /// Renders `expression` with a `^~~~` marker under the part that caused the error
+    ///
+    /// Returns [None] if the error doesn't point at a specific part of the expression
+    pub fn underline(&self, expression: &str) -> Option<String> {
+        let span = self.span()?;
+
+        let start = span.start.min(expression.len());
+        let end = span.end.clamp(start, expression.len());
+
+        // Count characters rather than bytes so multibyte names like π line up
+        let padding = expression[..start].chars().count();
+        let width = expression[start..end].chars().count().max(1);
+
+        Some(format!(
+            "{expression}\n{}{color_red}^{}{color_reset}",
+            " ".repeat(padding),
+            "~".repeat(width - 1)
+        ))
+    }
+}
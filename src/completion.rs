@@ -0,0 +1,123 @@
+//! Tab completion for the prompt line
+use crate::{COMMANDS, FUNCTIONS, VARIABLES};
+
+/// Names that can complete the word before `cursor` in `line`, sorted and without duplicates
+///
+/// Returns the byte index the word starts at along with the candidates.
+/// At the start of the line commands come first, since that's the only place they can be run, followed by everything else
+pub fn candidates(line: &str, cursor: usize) -> (usize, Vec<String>) {
+    let before = &line[..cursor];
+    let start = before.trim_end_matches(is_name_character).len();
+    let prefix = &before[start..];
+
+    if prefix.is_empty() {
+        return (start, Vec::new());
+    }
+
+    let mut names = Vec::new();
+    if before[..start].trim().is_empty() {
+        names.extend(sorted_matches(command_names(), prefix));
+    }
+
+    let other_names = variable_names().into_iter().chain(function_names());
+    for name in sorted_matches(other_names.collect(), prefix) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    (start, names)
+}
+
+/// The longest text every candidate starts with
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some((first, rest)) = candidates.split_first() else {
+        return String::new();
+    };
+
+    let mut prefix = first.as_str();
+    for candidate in rest {
+        let common = prefix
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or(prefix.len().min(candidate.len()));
+
+        prefix = &prefix[..common];
+    }
+
+    prefix.to_owned()
+}
+
+fn sorted_matches(mut names: Vec<String>, prefix: &str) -> Vec<String> {
+    names.retain(|name| name.starts_with(prefix));
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Names and aliases of every command
+fn command_names() -> Vec<String> {
+    let commands = COMMANDS.lock().unwrap();
+    commands
+        .values()
+        .flat_map(|command| {
+            std::iter::once(command.name).chain(command.aliases.iter().flatten().copied())
+        })
+        .map(|name| name.to_owned())
+        .collect()
+}
+
+/// Names and aliases of every variable and user-defined function
+fn variable_names() -> Vec<String> {
+    let variables = VARIABLES.lock().unwrap();
+    let aliases = variables
+        .variables
+        .values()
+        .flat_map(|variable| variable.aliases.iter().flatten().cloned());
+
+    variables
+        .variables
+        .keys()
+        .cloned()
+        .chain(aliases)
+        .chain(variables.functions.keys().cloned())
+        .collect()
+}
+
+/// Names and aliases of every built-in function
+fn function_names() -> Vec<String> {
+    let functions = FUNCTIONS.lock().unwrap();
+    functions
+        .values()
+        .flat_map(|function| {
+            std::iter::once(function.name).chain(function.aliases.iter().flatten().copied())
+        })
+        .map(|name| name.to_owned())
+        .collect()
+}
+
+/// Whether `c` can be part of a name, the same as in the [lexer](crate::lexer)
+fn is_name_character(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '°'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn common_prefix_of_candidates() {
+        assert_eq!(common_prefix(&[]), "");
+        assert_eq!(common_prefix(&strings(&["sqrt"])), "sqrt");
+        assert_eq!(common_prefix(&strings(&["sin", "sinh", "sign"])), "si");
+        assert_eq!(common_prefix(&strings(&["sinh", "sin"])), "sin");
+        assert_eq!(common_prefix(&strings(&["abs", "max"])), "");
+        assert_eq!(common_prefix(&strings(&["°C", "°F"])), "°");
+    }
+}
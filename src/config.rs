@@ -1,5 +1,93 @@
 use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    angle_mode::AngleMode, edit_mode::EditMode, fraction_style::FractionStyle, precision::Precision,
+};
+
 #[derive(Deserialize, Serialize)]
 pub struct Config {
     pub time_expression: bool,
+    /// Unit trigonometric functions use for angles
+    #[serde(default)]
+    pub angle_mode: AngleMode,
+    #[serde(default)]
+    pub precision: Precision,
+    /// Decimal places to show exact results with, 16 if not set
+    #[serde(default)]
+    pub digits: Option<usize>,
+    /// Whether exact results are also shown as fractions or mixed numbers
+    #[serde(default)]
+    pub fractions: FractionStyle,
+    /// Whether user-defined variables and functions are saved to the workspace file on exit and loaded on start-up
+    #[serde(default = "default_save_workspace")]
+    pub save_workspace: bool,
+    /// File the workspace is saved to, next to the config by default
+    #[serde(default = "default_workspace_file")]
+    pub workspace_file: String,
+    /// Whether expressions are saved to the history file on exit so they can be recalled in later sessions
+    #[serde(default = "default_save_history")]
+    pub save_history: bool,
+    /// File history is saved to, next to the config by default
+    #[serde(default = "default_history_file")]
+    pub history_file: String,
+    /// Most expressions kept in the history file
+    #[serde(default = "default_history_size")]
+    pub history_size: usize,
+    /// Whether an expression entered more than once is only kept once, at its most recent position
+    #[serde(default = "default_deduplicate_history")]
+    pub deduplicate_history: bool,
+    /// Key bindings for editing the prompt line
+    #[serde(default)]
+    pub edit_mode: EditMode,
+    /// Whether the result of the line being typed is shown below the prompt before it's entered
+    #[serde(default = "default_preview_results")]
+    pub preview_results: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            time_expression: false,
+            angle_mode: AngleMode::default(),
+            precision: Precision::default(),
+            digits: None,
+            fractions: FractionStyle::default(),
+            save_workspace: default_save_workspace(),
+            workspace_file: default_workspace_file(),
+            save_history: default_save_history(),
+            history_file: default_history_file(),
+            history_size: default_history_size(),
+            deduplicate_history: default_deduplicate_history(),
+            edit_mode: EditMode::default(),
+            preview_results: default_preview_results(),
+        }
+    }
+}
+
+fn default_save_workspace() -> bool {
+    true
+}
+
+fn default_workspace_file() -> String {
+    "./workspace.toml".to_owned()
+}
+
+fn default_save_history() -> bool {
+    true
+}
+
+fn default_history_file() -> String {
+    "./history.txt".to_owned()
+}
+
+fn default_history_size() -> usize {
+    1000
+}
+
+fn default_deduplicate_history() -> bool {
+    true
+}
+
+fn default_preview_results() -> bool {
+    true
 }
@@ -0,0 +1,225 @@
+//! Catalogue of built-in mathematical and physical constants
+use core::f64;
+
+use crate::unit::Dimension;
+
+/// A named constant, like `pi` or the speed of light `c`
+pub struct Constant {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Value in SI base units
+    pub value: f64,
+    pub dimension: Dimension,
+    /// Unit the constant is shown in, empty for plain numbers
+    pub unit: &'static str,
+    /// Category the `constants` command groups the constant under
+    pub category: &'static str,
+    pub description: &'static str,
+}
+
+const MATHEMATICAL: &str = "Mathematical";
+const PHYSICAL: &str = "Physical";
+
+/// Every built-in constant, in the order they're listed
+///
+/// Physical constants use the CODATA 2018 values, most of which are exact by definition of the SI units
+pub const CONSTANTS: &[Constant] = &[
+    Constant {
+        name: "pi",
+        aliases: &["π"],
+        value: f64::consts::PI,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Ratio of a circle's circumference to its diameter",
+    },
+    Constant {
+        name: "tau",
+        aliases: &["τ"],
+        value: f64::consts::TAU,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Ratio of a circle's circumference to its radius, 2π",
+    },
+    Constant {
+        name: "e",
+        aliases: &["euler"],
+        value: f64::consts::E,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Euler's number, the base of the natural logarithm",
+    },
+    Constant {
+        name: "phi",
+        aliases: &["φ", "golden_ratio"],
+        value: 1.618033988749895,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Golden ratio, (1 + √5) / 2",
+    },
+    Constant {
+        name: "γ",
+        aliases: &["ℇ", "euler_gamma"],
+        value: 0.5772156649015329,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Euler–Mascheroni constant",
+    },
+    Constant {
+        name: "sqrt2",
+        aliases: &[],
+        value: f64::consts::SQRT_2,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Square root of 2, Pythagoras' constant",
+    },
+    Constant {
+        name: "ln2",
+        aliases: &[],
+        value: f64::consts::LN_2,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: MATHEMATICAL,
+        description: "Natural logarithm of 2",
+    },
+    Constant {
+        name: "c",
+        aliases: &["speed_of_light"],
+        value: 299792458.0,
+        dimension: Dimension([1, 0, -1, 0, 0, 0, 0, 0]),
+        unit: "m/s",
+        category: PHYSICAL,
+        description: "Speed of light in vacuum",
+    },
+    Constant {
+        name: "h",
+        aliases: &["planck"],
+        value: 6.62607015e-34,
+        dimension: Dimension([2, 1, -1, 0, 0, 0, 0, 0]),
+        unit: "J*s",
+        category: PHYSICAL,
+        description: "Planck constant (h after a number, like 5 h, is still hours)",
+    },
+    Constant {
+        name: "ħ",
+        aliases: &["hbar"],
+        value: 1.054571817e-34,
+        dimension: Dimension([2, 1, -1, 0, 0, 0, 0, 0]),
+        unit: "J*s",
+        category: PHYSICAL,
+        description: "Reduced Planck constant, h / 2π",
+    },
+    Constant {
+        name: "G",
+        aliases: &["gravitational_constant"],
+        value: 6.6743e-11,
+        dimension: Dimension([3, -1, -2, 0, 0, 0, 0, 0]),
+        unit: "m^3/(kg*s^2)",
+        category: PHYSICAL,
+        description: "Newtonian constant of gravitation",
+    },
+    Constant {
+        name: "g0",
+        aliases: &["standard_gravity"],
+        value: 9.80665,
+        dimension: Dimension([1, 0, -2, 0, 0, 0, 0, 0]),
+        unit: "m/s^2",
+        category: PHYSICAL,
+        description: "Standard acceleration of gravity",
+    },
+    Constant {
+        name: "k_B",
+        aliases: &["boltzmann"],
+        value: 1.380649e-23,
+        dimension: Dimension([2, 1, -2, 0, -1, 0, 0, 0]),
+        unit: "J/K",
+        category: PHYSICAL,
+        description: "Boltzmann constant",
+    },
+    Constant {
+        name: "N_A",
+        aliases: &["avogadro"],
+        value: 6.02214076e23,
+        dimension: Dimension([0, 0, 0, 0, 0, -1, 0, 0]),
+        unit: "1/mol",
+        category: PHYSICAL,
+        description: "Avogadro constant",
+    },
+    Constant {
+        name: "R",
+        aliases: &["gas_constant"],
+        value: 8.314462618,
+        dimension: Dimension([2, 1, -2, 0, -1, -1, 0, 0]),
+        unit: "J/(mol*K)",
+        category: PHYSICAL,
+        description: "Molar gas constant, N_A * k_B",
+    },
+    Constant {
+        name: "q_e",
+        aliases: &["elementary_charge"],
+        value: 1.602176634e-19,
+        dimension: Dimension([0, 0, 1, 1, 0, 0, 0, 0]),
+        unit: "C",
+        category: PHYSICAL,
+        description: "Elementary charge, the charge of a proton",
+    },
+    Constant {
+        name: "m_e",
+        aliases: &["electron_mass"],
+        value: 9.1093837015e-31,
+        dimension: Dimension([0, 1, 0, 0, 0, 0, 0, 0]),
+        unit: "kg",
+        category: PHYSICAL,
+        description: "Electron mass",
+    },
+    Constant {
+        name: "m_p",
+        aliases: &["proton_mass"],
+        value: 1.67262192369e-27,
+        dimension: Dimension([0, 1, 0, 0, 0, 0, 0, 0]),
+        unit: "kg",
+        category: PHYSICAL,
+        description: "Proton mass",
+    },
+    Constant {
+        name: "ε0",
+        aliases: &["epsilon0"],
+        value: 8.8541878128e-12,
+        dimension: Dimension([-3, -1, 4, 2, 0, 0, 0, 0]),
+        unit: "F/m",
+        category: PHYSICAL,
+        description: "Vacuum electric permittivity",
+    },
+    Constant {
+        name: "μ0",
+        aliases: &["mu0"],
+        value: 1.25663706212e-6,
+        dimension: Dimension([1, 1, -2, -2, 0, 0, 0, 0]),
+        unit: "N/A^2",
+        category: PHYSICAL,
+        description: "Vacuum magnetic permeability",
+    },
+    Constant {
+        name: "σ",
+        aliases: &["stefan_boltzmann"],
+        value: 5.670374419e-8,
+        dimension: Dimension([0, 1, -3, 0, -4, 0, 0, 0]),
+        unit: "W/(m^2*K^4)",
+        category: PHYSICAL,
+        description: "Stefan–Boltzmann constant",
+    },
+    Constant {
+        name: "α",
+        aliases: &["fine_structure"],
+        value: 7.2973525693e-3,
+        dimension: Dimension::NONE,
+        unit: "",
+        category: PHYSICAL,
+        description: "Fine-structure constant, about 1/137",
+    },
+];
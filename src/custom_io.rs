@@ -1,4 +1,7 @@
-use std::io::{stdout, Write};
+use std::{
+    io::{stdout, Write},
+    sync::MutexGuard,
+};
 
 use crossterm::{
     cursor::{self, position},
@@ -8,150 +11,478 @@ use crossterm::{
 };
 use inline_colorization::*;
 
-use crate::*;
+use crate::{
+    completion,
+    edit_mode::{EditMode, ViState},
+    highlight,
+    line_buffer::{KillRing, LineBuffer},
+    *,
+};
 
+/// Reads a line from the user, with the cursor movement, editing, and history keys of a readline-style prompt
+///
+/// The keys follow the [EditMode] in [CONFIG]. The returned line ends with a newline
 pub fn read_line() -> io::Result<String> {
-    let mut line = String::new();
     let (original_x, _) = position()?;
     _ = stdout().execute(cursor::SavePosition);
     _ = enable_raw_mode();
 
-    let mut current_history_entry = -1;
+    let mut editor = Editor {
+        line: LineBuffer::default(),
+        original_x,
+        // Everything that can be recalled with the arrow keys, including previous sessions
+        history: history::recall(),
+        history_index: -1,
+        kill_ring: KILL_RING.lock().unwrap(),
+        edit_mode: EditMode::current(),
+        vi_state: ViState::Insert,
+        pending_operator: None,
+        completion: None,
+        hint: None,
+        preview_results: CONFIG
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|config| config.preview_results),
+    };
+
+    loop {
+        // Other events like resizing the terminal don't change the line
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
 
-    while let Event::Key(KeyEvent {
-        code,
-        modifiers,
-        kind,
-        ..
-    }) = event::read()?
-    {
         // Prevents key presses from being registered twice on Windows
         if kind == KeyEventKind::Release {
             continue;
         }
 
-        let (x, _) = position()?;
-        let mut clear_line = |flush| -> io::Result<()> {
-            if !line.is_empty() {
-                let len = line.len();
-                line.clear();
-                stdout().queue(cursor::MoveLeft(len as u16))?;
-                print!("{}", " ".repeat(len));
-                stdout().queue(cursor::MoveLeft(len as u16))?;
-                if flush {
-                    stdout().flush()?;
+        if code == KeyCode::Enter {
+            editor.clear_below()?;
+            _ = disable_raw_mode();
+            println!();
+            return Ok(format!("{}\n", editor.line.text()));
+        }
+
+        editor.handle_key(code, modifiers)?;
+        if editor.hint.is_none() {
+            editor.hint = editor.preview();
+        }
+
+        editor.redraw()?;
+    }
+}
+
+/// State of the line being read by [read_line]
+struct Editor<'a> {
+    line: LineBuffer,
+    /// Column the line starts at, right after the prompt
+    original_x: u16,
+    history: Vec<String>,
+    /// Index of the history entry being shown, or -1 for the line being typed
+    history_index: isize,
+    kill_ring: MutexGuard<'a, KillRing>,
+    edit_mode: EditMode,
+    vi_state: ViState,
+    /// Operator like the `d` in `dw`, waiting for its motion in vi's normal state
+    pending_operator: Option<char>,
+    /// Names being cycled through with Tab
+    completion: Option<Completion>,
+    /// Dimmed text shown on the line below the prompt
+    hint: Option<String>,
+    /// Whether the line's result is previewed as the [hint](Editor::hint)
+    preview_results: bool,
+}
+
+impl Editor<'_> {
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> io::Result<()> {
+        if modifiers == KeyModifiers::CONTROL && code == KeyCode::Char('c') {
+            exit_from_raw_mode();
+        }
+
+        if code == KeyCode::Tab {
+            self.complete();
+            return Ok(());
+        }
+
+        self.completion = None;
+        self.hint = None;
+
+        match (self.edit_mode, self.vi_state) {
+            (EditMode::Vi, ViState::Normal) => {
+                let is_chord = modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+                match code {
+                    KeyCode::Char(c) if !is_chord => self.vi_normal_key(c),
+                    // Chords like Ctrl-D and Ctrl-R work the same as in insert state
+                    KeyCode::Char(_) => self.emacs_key(code, modifiers)?,
+                    _ => self.movement_key(code, modifiers),
                 }
+
+                self.clamp_in_normal_state();
             }
+            (EditMode::Vi, ViState::Insert) => match code {
+                KeyCode::Esc => self.enter_normal_state(),
+                // Esc followed quickly by a key arrives as Alt and that key
+                KeyCode::Char(c) if modifiers == KeyModifiers::ALT => {
+                    self.enter_normal_state();
+                    self.vi_normal_key(c);
+                    self.clamp_in_normal_state();
+                }
+                _ => self.emacs_key(code, modifiers)?,
+            },
+            (EditMode::Emacs, _) => self.emacs_key(code, modifiers)?,
+        }
 
-            Ok(())
-        };
+        Ok(())
+    }
 
-        match code {
-            KeyCode::Enter => {
-                _ = disable_raw_mode();
-                println!();
-                line.push('\n');
-                break;
-            }
-            // Grab last expression from history and replace current one with it
-            KeyCode::Up => {
-                let history = HISTORY.lock().unwrap();
-
-                if !history.is_empty() {
-                    current_history_entry += 1;
-                    if history.len() <= (current_history_entry as usize) {
-                        current_history_entry = history.len() as isize;
-                        continue;
-                    }
+    /// Keys that work the same in every mode, like the arrow keys
+    fn movement_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let control = modifiers == KeyModifiers::CONTROL;
 
-                    clear_line(false)?;
+        match code {
+            KeyCode::Up => self.history_older(),
+            KeyCode::Down => self.history_newer(),
+            KeyCode::Left if control => self.line.move_word_left(),
+            KeyCode::Right if control => self.line.move_word_right(),
+            KeyCode::Left => self.line.move_left(),
+            KeyCode::Right => self.line.move_right(),
+            KeyCode::Home => self.line.move_home(),
+            KeyCode::End => self.line.move_end(),
+            KeyCode::Delete => self.line.delete_forward(),
+            _ => (),
+        }
+    }
 
-                    let mut entry = history
-                        .get(current_history_entry as usize)
-                        .unwrap()
-                        .to_owned();
+    /// readline's default key bindings, which are also used in vi's insert state
+    fn emacs_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> io::Result<()> {
+        let control = modifiers == KeyModifiers::CONTROL;
+        let alt = modifiers == KeyModifiers::ALT;
+        let line = &mut self.line;
+        let kill_ring = &mut self.kill_ring;
 
-                    entry = entry.trim().to_owned();
+        match code {
+            KeyCode::Backspace if alt => line.kill_word_backward(kill_ring),
+            KeyCode::Backspace => line.delete_backward(),
+            KeyCode::Char(c) if control => match c {
+                // Ctrl-D only exits on an empty line, otherwise it deletes forward like in readline
+                'd' if line.is_empty() => exit_from_raw_mode(),
+                'd' => line.delete_forward(),
+                'a' => line.move_home(),
+                'e' => line.move_end(),
+                'b' => line.move_left(),
+                'f' => line.move_right(),
+                'k' => line.kill_to_end(kill_ring),
+                'u' => line.kill_to_start(kill_ring),
+                'w' => line.kill_word_backward(kill_ring),
+                'y' => line.yank(kill_ring),
+                // Terminals send Ctrl-_ and Ctrl-/ as the same byte as Ctrl-7
+                '_' | '/' | '7' => line.undo(),
+                'r' => {
+                    if let Some(entry) =
+                        reverse_search(&self.history, self.original_x, line.text())?
+                    {
+                        line.set(&entry);
+                    }
+                }
+                _ => (),
+            },
+            KeyCode::Char('b') if alt => line.move_word_left(),
+            KeyCode::Char('f') if alt => line.move_word_right(),
+            KeyCode::Char('d') if alt => line.kill_word_forward(kill_ring),
+            KeyCode::Char('y') if alt => line.yank_pop(kill_ring),
+            KeyCode::Char(c) if !alt => line.insert(c),
+            _ => self.movement_key(code, modifiers),
+        }
 
-                    print!("{}", &entry);
+        Ok(())
+    }
 
-                    line = entry;
+    /// vi's normal state, where keys move around and edit instead of typing
+    fn vi_normal_key(&mut self, c: char) {
+        let line = &mut self.line;
+        let kill_ring = &mut self.kill_ring;
 
-                    stdout().flush()?;
+        // The second key of dw or cw
+        if let Some(operator) = self.pending_operator.take() {
+            match (operator, c) {
+                ('d', 'w') => line.kill_to_next_word(kill_ring),
+                ('c', 'w') => {
+                    // Like in vim, cw changes to the end of the word rather than the start of the next one
+                    line.kill_word_forward(kill_ring);
+                    self.vi_state = ViState::Insert;
                 }
+                _ => (),
             }
-            // Move cursor left (within bounds)
-            KeyCode::Left => {
-                if original_x != x {
-                    stdout().execute(cursor::MoveLeft(1))?;
-                }
+
+            return;
+        }
+
+        match c {
+            'h' => line.move_left(),
+            'l' => line.move_right(),
+            'w' => line.move_next_word(),
+            'b' => line.move_word_left(),
+            '0' => line.move_home(),
+            '$' => line.move_end(),
+            'x' => line.delete_forward(),
+            'u' => line.undo(),
+            'k' => self.history_older(),
+            'j' => self.history_newer(),
+            'd' | 'c' => self.pending_operator = Some(c),
+            'i' => self.vi_state = ViState::Insert,
+            'a' => {
+                line.move_right();
+                self.vi_state = ViState::Insert;
             }
-            // Move cursor right (within bounds)
-            KeyCode::Right => {
-                let prompt_length = HISTORY.lock().unwrap().len() + 5;
-                if (x as usize) < (line.len() + prompt_length) {
-                    stdout().execute(cursor::MoveRight(1))?;
-                }
+            'I' => {
+                line.move_home();
+                self.vi_state = ViState::Insert;
             }
-            // Go down in history or clear input
-            KeyCode::Down => {
-                let history = HISTORY.lock().unwrap();
-
-                if !history.is_empty() {
-                    current_history_entry -= 1;
-                    if current_history_entry < 0 {
-                        current_history_entry = -1;
-                        clear_line(true)?;
-                        continue;
-                    }
+            'A' => {
+                line.move_end();
+                self.vi_state = ViState::Insert;
+            }
+            _ => (),
+        }
+    }
+
+    /// Leaves vi's insert state, moving back onto the last character typed like vim does
+    fn enter_normal_state(&mut self) {
+        self.vi_state = ViState::Normal;
+        self.line.move_left();
+    }
+
+    /// Keeps the cursor on a character in vi's normal state, unless the last key like `A` or `cw` switched to insert
+    fn clamp_in_normal_state(&mut self) {
+        if self.vi_state == ViState::Normal {
+            self.line.clamp_to_last_character();
+        }
+    }
+
+    /// Replaces the line with the previous entry in history
+    fn history_older(&mut self) {
+        if self.history_index + 1 < self.history.len() as isize {
+            self.history_index += 1;
+            self.line.set(&self.history[self.history_index as usize]);
+        }
+    }
+
+    /// Replaces the line with the next entry in history, or clears it after the most recent one
+    fn history_newer(&mut self) {
+        if self.history_index > 0 {
+            self.history_index -= 1;
+            self.line.set(&self.history[self.history_index as usize]);
+        } else {
+            self.history_index = -1;
+            self.line.set("");
+        }
+    }
+
+    /// Shows the line after the prompt, with the terminal cursor where the line's cursor is
+    ///
+    /// The [hint](Editor::hint) is shown on the line below, and anything else below the prompt is cleared
+    fn redraw(&self) -> io::Result<()> {
+        let mut stdout = stdout();
 
-                    clear_line(false)?;
+        // The vi state may have changed, so its marker at the start of the prompt is redrawn
+        if self.edit_mode == EditMode::Vi {
+            stdout.queue(cursor::MoveToColumn(0))?;
+            print!("{color_yellow}{}{color_reset}", self.vi_state.indicator());
+        }
 
-                    let entry = history
-                        .get(current_history_entry as usize)
-                        .unwrap()
-                        .trim()
-                        .to_owned();
+        stdout.queue(cursor::MoveToColumn(self.original_x))?;
+        print!("{}", highlight::highlight(self.line.text()));
+        stdout.queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
 
-                    print!("{}", &entry);
+        if let Some(hint) = &self.hint {
+            // Kept to one line so moving back up always lands on the prompt
+            let (width, _) = terminal::size()?;
+            let hint: String = hint
+                .chars()
+                .take(width.saturating_sub(1) as usize)
+                .collect();
+            print!("\r\n{ITALIC}{color_bright_black}{hint}{RESET}");
+            stdout.queue(cursor::MoveUp(1))?;
+        }
 
-                    line = entry;
+        stdout.queue(cursor::MoveToColumn(
+            self.original_x + self.line.cursor_column() as u16,
+        ))?;
+        stdout.flush()
+    }
 
-                    stdout().flush()?;
-                } else {
-                    clear_line(true)?;
-                }
+    /// Result or error the line would give if it were entered, see [parser::preview]
+    fn preview(&self) -> Option<String> {
+        if !self.preview_results {
+            return None;
+        }
+
+        match parser::preview(self.line.text())? {
+            Ok(result) => Some(format!("= {}", parser::format_result(&result))),
+            Err(err) => Some(format!("{}: {err}", err.code())),
+        }
+    }
+
+    /// Clears anything shown below the prompt, like the list of completions
+    fn clear_below(&self) -> io::Result<()> {
+        stdout()
+            .queue(cursor::MoveToColumn(
+                self.original_x + self.line.text().chars().count() as u16,
+            ))?
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        stdout().flush()
+    }
+
+    /// Completes the name before the cursor, like Tab in a shell
+    ///
+    /// If there's more than one candidate, the part they have in common is filled in and they're listed under the prompt.
+    /// Pressing Tab again cycles through them
+    fn complete(&mut self) {
+        if let Some(completion) = &mut self.completion {
+            let index = completion
+                .index
+                .map_or(0, |i| (i + 1) % completion.candidates.len());
+            completion.index = Some(index);
+            self.line
+                .replace_before_cursor(completion.start, &completion.candidates[index]);
+            return;
+        }
+
+        let (start, candidates) = completion::candidates(self.line.text(), self.line.cursor());
+        match candidates.as_slice() {
+            [] => self.hint = None,
+            [candidate] => {
+                self.line.replace_before_cursor(start, candidate);
+                self.hint = None;
             }
-            KeyCode::Backspace | KeyCode::Delete => {
-                if !line.is_empty() {
-                    _ = line.pop();
-                    _ = stdout().queue(cursor::MoveLeft(1));
-                    print!(" ");
-                    _ = stdout().execute(cursor::MoveLeft(1));
+            _ => {
+                let prefix = completion::common_prefix(&candidates);
+                if prefix.len() > self.line.cursor() - start {
+                    self.line.replace_before_cursor(start, &prefix);
                 }
+
+                self.hint = Some(candidates.join("  "));
+                self.completion = Some(Completion {
+                    start,
+                    candidates,
+                    index: None,
+                });
+            }
+        }
+    }
+}
+
+/// Names that can complete what's being typed, for cycling through with Tab
+struct Completion {
+    /// Byte index the name being completed starts at
+    start: usize,
+    candidates: Vec<String>,
+    /// Candidate currently filled in, [None] before cycling starts
+    index: Option<usize>,
+}
+
+/// Leaves raw mode and exits, for Ctrl-C and Ctrl-D
+fn exit_from_raw_mode() {
+    _ = disable_raw_mode();
+    println!();
+    _ = default_commands::exit(None);
+}
+
+/// Searches history for an expression containing what the user types, like Ctrl-R in readline
+///
+/// The most recent match is shown as the user types, pressing Ctrl-R again goes to the next older match.
+/// Returns the match if the user presses Enter, or [None] if they cancel with Esc
+fn reverse_search(history: &[String], original_x: u16, line: &str) -> io::Result<Option<String>> {
+    let mut query = String::new();
+    // Where in history to start looking for a match, most recent first
+    let mut start = 0;
+
+    loop {
+        let found = if query.is_empty() {
+            None
+        } else {
+            history
+                .iter()
+                .enumerate()
+                .skip(start)
+                .find(|(_, entry)| entry.contains(&query))
+                .map(|(i, _)| i)
+        };
+
+        let entry = found.map(|i| history[i].as_str()).unwrap_or(line);
+        let status = match (found, query.is_empty()) {
+            (None, false) => "failed search",
+            _ => "search",
+        };
+
+        // The part of the entry that matches is highlighted
+        let highlighted = match found {
+            Some(_) => entry.replacen(
+                &query,
+                &format!("{style_underline}{color_yellow}{query}{style_reset}"),
+                1,
+            ),
+            None => entry.to_owned(),
+        };
+        redraw(
+            original_x,
+            &format!("{color_cyan}({status}){color_reset} {query}: {highlighted}"),
+        )?;
+
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+
+        if kind == KeyEventKind::Release {
+            continue;
+        }
+
+        match code {
+            KeyCode::Enter => return Ok(found.map(|i| history[i].clone())),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Backspace => {
+                _ = query.pop();
+                start = 0;
             }
-            KeyCode::Char(c) => {
-                // CTRL
-                if modifiers == KeyModifiers::CONTROL {
-                    match c {
-                        'c' | 'd' => {
-                            _ = disable_raw_mode();
-                            println!();
-                            default_commands::exit(None);
-                        }
-                        _ => (),
+            // Look further back for the same query
+            KeyCode::Char('r') if modifiers == KeyModifiers::CONTROL => {
+                // Stays on the oldest match once there are no more
+                if let Some(i) = found {
+                    if history[i + 1..].iter().any(|entry| entry.contains(&query)) {
+                        start = i + 1;
                     }
-                } else {
-                    line.push(c);
-                    print!("{c}");
-                    stdout().flush()?;
                 }
             }
-            _ => {}
+            KeyCode::Char('c' | 'g') if modifiers == KeyModifiers::CONTROL => return Ok(None),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                query.push(c);
+                start = found.unwrap_or(0);
+            }
+            _ => (),
         }
     }
+}
 
-    Ok(line)
+/// Replaces everything after `original_x` on the current line with `text`
+fn redraw(original_x: u16, text: &str) -> io::Result<()> {
+    stdout()
+        .queue(cursor::MoveToColumn(original_x))?
+        .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
+    print!("{text}");
+    stdout().flush()
 }
 
 /// This is a helper function that replaces the `#` in `[#]> expression` with `marker`
@@ -1,19 +1,38 @@
 //! Built-in commands
-use std::io::stdout;
+use std::{io::stdout, path::PathBuf};
 
 use crossterm::{cursor, terminal, ExecutableCommand, QueueableCommand};
 use custom_io::mark_special;
 use inline_colorization::*;
+use num_bigint::BigInt;
 use parameter_documentation::ParameterDocumentation;
-use radix_fmt::radix;
-use variable::Variable;
-
-use crate::{command::Command, *};
-
-/// Prints a general help message, or help for a specific command
-pub fn help(command_name: Option<&str>) {
+use variable::{Variable, VariableKind};
+
+use crate::{
+    angle_mode::AngleMode,
+    builtin_function::BuiltinFunction,
+    calc_error::CalcError,
+    command::Command,
+    constants::CONSTANTS,
+    edit_mode::EditMode,
+    fraction_style::FractionStyle,
+    number::{Number, DEFAULT_DIGITS, MAX_DIGITS},
+    precision::Precision,
+    *,
+};
+
+/// Prints a general help message, or help for a specific command, built-in function, or error code
+pub fn help(command_name: Option<&str>) -> Result<(), CalcError> {
     let commands = COMMANDS.lock().unwrap();
     if let Some(command_name) = command_name {
+        if let Some(explanation) = CalcError::explain(command_name) {
+            println!(
+                "{color_red}{style_bold}{}{RESET} - {explanation}",
+                command_name.to_uppercase()
+            );
+            return Ok(());
+        }
+
         let mut command: Option<&Command> = None;
         for (name, cmd) in commands.iter() {
             if command_name == name {
@@ -30,8 +49,15 @@ pub fn help(command_name: Option<&str>) {
         }
 
         if command.is_none() {
-            println!("{color_red}Command \"{command_name}\" not found{RESET}");
-            return;
+            if let Some(function) = BuiltinFunction::find(command_name) {
+                function_help(&function);
+                return Ok(());
+            }
+
+            return Err(CalcError::CommandArgument {
+                command: "help".to_owned(),
+                message: format!("Command or function \"{command_name}\" not found"),
+            });
         }
 
         let command = command.unwrap();
@@ -53,7 +79,7 @@ pub fn help(command_name: Option<&str>) {
             }
         }
 
-        return;
+        return Ok(());
     }
 
     println!("SmartCalc is an advanced command-line calculator with features such as variables, comments, line references, and continuation.\nFor an example of these features, run the {color_yellow}features{color_reset} command\n\n{color_blue}Commands{color_reset}\nName (Aliases) - Help Text\n");
@@ -73,47 +99,251 @@ pub fn help(command_name: Option<&str>) {
             command.help_text.unwrap_or("No help text"),
         );
     }
+
+    println!("\nErrors are shown with a code like {color_red}E008{color_reset}, run {color_yellow}help <code>{color_reset} to learn more about one");
+
+    Ok(())
 }
 
-/// Shows user all built-in and user-defined variables and their values
-pub fn show_variables(_: Option<&str>) {
-    let variables = VARIABLES.lock().unwrap();
-    let builtin_var_count = BUILTIN_VARIABLE_COUNT.lock().unwrap();
-    println!("{color_cyan}{ITALIC}Built-in:{RESET}");
+/// Prints help for a specific built-in function
+fn function_help(function: &BuiltinFunction) {
+    println!(
+        "{color_yellow}{style_bold}{}{RESET} - {}",
+        function.signature(),
+        function.help_text.unwrap_or("No help text")
+    );
+
+    if let Some(aliases) = &function.aliases {
+        println!("Aliases: {}", aliases.join(","));
+    }
 
-    let actual_variables: Vec<&Variable> = variables.variables.values().collect();
+    println!("Takes {} argument(s)", function.arity());
+
+    if let Some(parameters) = &function.parameter_documentation {
+        println!("\n{color_blue}Parameters{RESET}");
+        for (name, desc, expected_type) in parameters.clone().into_iter() {
+            println!("{color_magenta}{name}{RESET} - {desc} - Should be {color_magenta}{expected_type}{RESET}");
+        }
+    }
+}
+
+/// Lists all built-in functions
+pub fn functions(_: Option<&str>) -> Result<(), CalcError> {
+    let functions = FUNCTIONS.lock().unwrap();
+
+    let mut functions: Vec<&BuiltinFunction> = functions.values().collect();
+    functions.sort_by_key(|function| function.name);
+
+    println!("{color_blue}Functions{color_reset}\nSignature (Aliases) - Help Text\n");
+    for function in functions {
+        let aliases = if let Some(aliases) = &function.aliases {
+            aliases.join(", ")
+        } else {
+            "".to_owned()
+        };
 
-    for variable in actual_variables.iter().take(*builtin_var_count) {
         println!(
-            "{color_yellow}{}{color_reset} - {}",
-            variable.key, variable.value
+            "{color_yellow}{}{color_reset} ({}) - {}",
+            function.signature(),
+            aliases,
+            function.help_text.unwrap_or("No help text"),
         );
     }
 
-    if *builtin_var_count < actual_variables.len() {
-        println!("\n{color_cyan}{ITALIC}User-defined:{RESET}");
+    println!("\nRun {color_yellow}help <function>{color_reset} for more info about a function");
+
+    Ok(())
+}
+
+/// Shows user all built-in and user-defined variables and their values
+pub fn show_variables(_: Option<&str>) -> Result<(), CalcError> {
+    let variables = VARIABLES.lock().unwrap();
+
+    for (i, kind) in VariableKind::ALL.into_iter().enumerate() {
+        let of_kind: Vec<&Variable> = variables
+            .variables
+            .values()
+            .filter(|variable| variable.kind == kind)
+            .collect();
+
+        let is_empty = match kind {
+            VariableKind::Function => variables.functions.is_empty(),
+            _ => of_kind.is_empty(),
+        };
+
+        if is_empty {
+            continue;
+        }
+
+        let gap = if i == 0 { "" } else { "\n" };
+        println!("{gap}{color_cyan}{ITALIC}{kind}:{RESET}");
+
+        for variable in of_kind {
+            match kind {
+                VariableKind::Builtin => println!(
+                    "{color_yellow}{}{color_reset} - {}",
+                    variable.key, variable.value
+                ),
+                _ => println!("{} - {:.25}...", variable.key, variable.value),
+            }
+        }
+
+        if kind == VariableKind::Function {
+            for function in variables.functions.values() {
+                println!(
+                    "{color_yellow}{}{color_reset} = {}",
+                    function.signature(),
+                    function.source
+                );
+            }
+        }
     }
 
-    for variable in actual_variables.iter().skip(*builtin_var_count) {
-        println!("{} - {:.25}...", variable.key, variable.value);
+    Ok(())
+}
+
+/// Lists all built-in constants by category
+pub fn constants(_: Option<&str>) -> Result<(), CalcError> {
+    println!("{color_blue}Constants{color_reset}\nName (Aliases) = Value - Description");
+
+    let mut categories: Vec<&str> = CONSTANTS.iter().map(|constant| constant.category).collect();
+    categories.dedup();
+
+    for category in categories {
+        println!("\n{color_cyan}{ITALIC}{category}:{RESET}");
+
+        for constant in CONSTANTS
+            .iter()
+            .filter(|constant| constant.category == category)
+        {
+            let aliases = if constant.aliases.is_empty() {
+                "".to_owned()
+            } else {
+                format!(" ({})", constant.aliases.join(", "))
+            };
+
+            let value = Number::Float(constant.value);
+            let unit = if constant.unit.is_empty() {
+                "".to_owned()
+            } else {
+                format!(" {}", constant.unit)
+            };
+
+            println!(
+                "{color_yellow}{}{color_reset}{aliases} = {value}{unit} - {}",
+                constant.name, constant.description
+            );
+        }
     }
+
+    Ok(())
 }
 
 /// Shows the user the expression history
-pub fn show_history(_: Option<&str>) {
+pub fn show_history(_: Option<&str>) -> Result<(), CalcError> {
     let history = HISTORY.lock().unwrap();
-    let fixed: Vec<String> = history.iter().map(|elem| elem.replace("\n", "")).collect();
+    let fixed: Vec<String> = history
+        .iter()
+        .map(|elem| elem.expression.replace("\n", ""))
+        .collect();
     println!("{color_blue}History{RESET}\n{}", fixed.join("\n"));
     if !fixed.is_empty() {
         _ = stdout().queue(cursor::MoveUp(1));
         println!("{ITALIC}Very quiet here{RESET}");
     }
+
+    Ok(())
+}
+
+/// Declares a variable even if it replaces a built-in constant or `ans`
+pub fn shadow(declaration: Option<&str>) -> Result<(), CalcError> {
+    let Some(declaration) = declaration.filter(|declaration| declaration.contains('=')) else {
+        return Err(CalcError::CommandArgument {
+            command: "shadow".to_owned(),
+            message: "Expected a declaration like \"shadow pi = 3\"".to_owned(),
+        });
+    };
+
+    if declaration.matches('=').count() > 1 {
+        return Err(CalcError::TooManyEquals);
+    }
+
+    parser::parse_variable_declarations(declaration, true)
+}
+
+/// Removes user-defined variables and functions by name, alias, or glob like `tmp*`
+pub fn unset(names: Option<&str>) -> Result<(), CalcError> {
+    let Some(names) = names else {
+        return Err(CalcError::CommandArgument {
+            command: "unset".to_owned(),
+            message: "Expected the names of variables or functions to remove".to_owned(),
+        });
+    };
+
+    let mut variables = VARIABLES.lock().unwrap();
+    let mut removed: Vec<String> = Vec::new();
+
+    for name in names.split_whitespace() {
+        let removed_here = variables.remove(name);
+        if !removed_here.is_empty() {
+            removed.extend(removed_here);
+            continue;
+        }
+
+        match variables.kind(name) {
+            Some(VariableKind::Builtin) => {
+                println!("{color_yellow}{name} is built in and can't be removed{color_reset}")
+            }
+            Some(VariableKind::Result) => println!(
+                "{color_yellow}{name} always holds the last result and can't be removed{color_reset}"
+            ),
+            _ => println!("{color_yellow}Nothing named {name} to remove{color_reset}"),
+        }
+    }
+
+    if removed.is_empty() {
+        println!("{color_yellow}Nothing was removed{color_reset}");
+    } else {
+        println!("{color_green}Removed {}{color_reset}", removed.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Saves user-defined variables and functions to the workspace file, or another file if one is given
+pub fn save(file: Option<&str>) -> Result<(), CalcError> {
+    let file = match file {
+        Some(file) => file.to_owned(),
+        None => CONFIG
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .workspace_file
+            .clone(),
+    };
+
+    let path = PathBuf::from(&file);
+    match workspace::save(&path) {
+        Ok((variables, functions)) => {
+            println!("{color_green}Saved {variables} variable(s) and {functions} function(s) to {file}{color_reset}");
+            Ok(())
+        }
+        Err(err) => Err(CalcError::CommandArgument {
+            command: "save".to_owned(),
+            message: format!("Couldn't write {file}: {err}"),
+        }),
+    }
 }
 
 /// Exits cleanly
-pub fn exit(_: Option<&str>) {
+pub fn exit(_: Option<&str>) -> Result<(), CalcError> {
     mark_special("bye", "");
 
+    // Saved after the line is marked, so any errors aren't drawn over
+    workspace::save_on_exit();
+    history::save_on_exit();
+
     // Restore previous console mode on Windows
     #[cfg(windows)]
     unsafe {
@@ -124,42 +354,40 @@ pub fn exit(_: Option<&str>) {
 }
 
 /// Clears expression history
-pub fn clear_history(_: Option<&str>) {
+pub fn clear_history(_: Option<&str>) -> Result<(), CalcError> {
     let mut history = HISTORY.lock().unwrap();
     history.clear();
+    PAST_HISTORY.lock().unwrap().clear();
     println!("{color_green}Cleared expression history{color_reset}");
+
+    Ok(())
 }
 
 /// Clears terminal and displays splash again
-pub fn clear_terminal(_: Option<&str>) {
+pub fn clear_terminal(_: Option<&str>) -> Result<(), CalcError> {
     _ = stdout()
         .queue(terminal::Clear(terminal::ClearType::All))
         .unwrap()
         .execute(cursor::MoveTo(0, 0));
 
     splash();
+
+    Ok(())
 }
 
 /// Clears all user-defined variables
-pub fn clear_variables(_: Option<&str>) {
+pub fn clear_variables(_: Option<&str>) -> Result<(), CalcError> {
     let mut variables = VARIABLES.lock().unwrap();
-    let builtin_var_count = BUILTIN_VARIABLE_COUNT.lock().unwrap();
-    let drain: Vec<(String, Variable)> = variables.variables.drain().collect();
-
-    for (i, (k, v)) in drain.into_iter().enumerate() {
-        if i == *builtin_var_count {
-            break;
-        }
+    variables.clear();
 
-        variables.variables.insert(k, v);
-    }
+    println!("{color_cyan}Cleared user-defined variables and functions{color_reset}");
 
-    println!("{color_cyan}Cleared user-defined variables{color_reset}");
+    Ok(())
 }
 
 #[allow(clippy::print_with_newline)]
 /// Shows off Smartcalc's features
-pub fn features(_: Option<&str>) {
+pub fn features(_: Option<&str>) -> Result<(), CalcError> {
     fn start(i: i32) {
         print!("{color_blue}[{color_cyan}{i}{color_blue}]>{color_reset}");
     }
@@ -184,17 +412,40 @@ pub fn features(_: Option<&str>) {
     print!("Finally, you can reference previous calculations by number:\n");
     start(5);
     print!(" [2] ^ 2\n{color_blue}= 10000{color_reset}\n\n");
+    print!("{color_blue}Functions{color_reset}\n");
+    print!("Functions are declared like variables, but with parameters:\n");
+    start(6);
+    print!(" f(x, y) = x^2 + y\n\n");
+    print!("And called like this:\n");
+    start(7);
+    print!(" f(3, 1)\n{color_blue}= 10{color_reset}\n\n");
 
     stdout().flush().unwrap();
+
+    Ok(())
 }
 
-/// Number converter for binary, octal, decimal, and hexadecimal numbers
-pub fn convert(number: Option<&str>) {
+/// Number converter for binary, octal, decimal, and hexadecimal numbers, or between units if given something like `5 mi to km`
+pub fn convert(number: Option<&str>) -> Result<(), CalcError> {
     let Some(number) = number else {
-        println!("{color_red}Number to convert not specified{RESET}");
-        return;
+        return Err(CalcError::CommandArgument {
+            command: "convert".to_owned(),
+            message: "Number to convert not specified".to_owned(),
+        });
     };
 
+    if number
+        .split_whitespace()
+        .any(|word| word == "to" || word == "in")
+    {
+        let result = parser::calculate(number)?;
+        println!(
+            "{color_blue}{}{color_reset}",
+            parser::format_result(&result)
+        );
+        return Ok(());
+    }
+
     let mut skip_prefix = true;
 
     let mut num = number.to_lowercase();
@@ -223,35 +474,168 @@ pub fn convert(number: Option<&str>) {
         num = num.chars().skip(2).collect();
     }
 
-    let result = isize::from_str_radix(&num, number_base);
-
-    if result.is_err() {
-        println!("{color_red}Could not parse number{RESET}");
-        return;
-    }
-
-    let actual_number = result.unwrap();
+    let Some(actual_number) = BigInt::parse_bytes(num.as_bytes(), number_base) else {
+        return Err(CalcError::CommandArgument {
+            command: "convert".to_owned(),
+            message: format!("Could not parse \"{number}\" as a base {number_base} number"),
+        });
+    };
 
     if number_base != 2 {
-        println!("{color_blue}Binary:{RESET} {}", radix(actual_number, 2));
+        println!(
+            "{color_blue}Binary:{RESET} {}",
+            actual_number.to_str_radix(2)
+        );
     }
 
     if number_base != 8 {
-        println!("{color_blue}Octal:{RESET} {}", radix(actual_number, 8));
+        println!(
+            "{color_blue}Octal:{RESET} {}",
+            actual_number.to_str_radix(8)
+        );
     }
 
     if number_base != 10 {
-        println!("{color_blue}Decimal:{RESET} {}", radix(actual_number, 10));
+        println!(
+            "{color_blue}Decimal:{RESET} {}",
+            actual_number.to_str_radix(10)
+        );
     }
 
     if number_base != 16 {
         println!(
             "{color_blue}Hexadecimal:{RESET} {}",
-            radix(actual_number, 16).to_string().to_uppercase()
+            actual_number.to_str_radix(16).to_uppercase()
         );
     }
 
     println!();
+
+    Ok(())
+}
+
+/// Shows or changes the unit trigonometric functions use for angles
+pub fn mode(angle_mode: Option<&str>) -> Result<(), CalcError> {
+    let Some(angle_mode) = angle_mode else {
+        println!(
+            "Angle mode is {color_magenta}{}{RESET}",
+            AngleMode::current()
+        );
+        return Ok(());
+    };
+
+    let Some(angle_mode) = AngleMode::from_name(angle_mode) else {
+        return Err(CalcError::CommandArgument {
+            command: "mode".to_owned(),
+            message: format!("\"{angle_mode}\" isn't an angle mode, expected deg, rad, or grad"),
+        });
+    };
+
+    let mut config = CONFIG.lock().unwrap();
+    config.as_mut().unwrap().angle_mode = angle_mode;
+
+    println!("{color_green}Angle mode set to {angle_mode}{color_reset}");
+
+    Ok(())
+}
+
+/// Shows or changes whether decimals are exact, and how many decimal places exact results are shown with
+pub fn precision(setting: Option<&str>) -> Result<(), CalcError> {
+    let mut config = CONFIG.lock().unwrap();
+    let config = config.as_mut().unwrap();
+
+    let Some(setting) = setting else {
+        println!(
+            "Precision is {color_magenta}{}{RESET}, showing exact results with up to {} decimal places",
+            config.precision,
+            config.digits.unwrap_or(DEFAULT_DIGITS)
+        );
+        return Ok(());
+    };
+
+    match setting.to_lowercase().as_str() {
+        "float" => config.precision = Precision::Float,
+        "exact" => config.precision = Precision::Exact,
+        digits => {
+            let Ok(digits) = digits.parse::<usize>() else {
+                return Err(CalcError::CommandArgument {
+                    command: "precision".to_owned(),
+                    message: format!(
+                        "\"{setting}\" isn't a precision, expected float, exact, or a number of decimal places"
+                    ),
+                });
+            };
+
+            if digits > MAX_DIGITS {
+                return Err(CalcError::CommandArgument {
+                    command: "precision".to_owned(),
+                    message: format!(
+                        "{digits} is too many decimal places, the most is {MAX_DIGITS}"
+                    ),
+                });
+            }
+
+            config.digits = Some(digits);
+            println!("{color_green}Showing exact results with up to {digits} decimal places{color_reset}");
+            return Ok(());
+        }
+    }
+
+    println!(
+        "{color_green}Precision set to {}{color_reset}",
+        config.precision
+    );
+
+    Ok(())
+}
+
+/// Shows or changes how exact fractional results are shown next to their decimal value
+pub fn fractions(style: Option<&str>) -> Result<(), CalcError> {
+    let Some(style) = style else {
+        println!(
+            "Fraction style is {color_magenta}{}{RESET}",
+            FractionStyle::current()
+        );
+        return Ok(());
+    };
+
+    let Some(style) = FractionStyle::from_name(style) else {
+        return Err(CalcError::CommandArgument {
+            command: "fractions".to_owned(),
+            message: format!(
+                "\"{style}\" isn't a fraction style, expected none, fraction, mixed, or both"
+            ),
+        });
+    };
+
+    let mut config = CONFIG.lock().unwrap();
+    config.as_mut().unwrap().fractions = style;
+
+    println!("{color_green}Fraction style set to {style}{color_reset}");
+
+    Ok(())
+}
+
+/// Shows or changes the key bindings used to edit the prompt line
+pub fn edit_mode(mode: Option<&str>) -> Result<(), CalcError> {
+    let Some(mode) = mode else {
+        println!("Edit mode is {color_magenta}{}{RESET}", EditMode::current());
+        return Ok(());
+    };
+
+    let Some(mode) = EditMode::from_name(mode) else {
+        return Err(CalcError::CommandArgument {
+            command: "editmode".to_owned(),
+            message: format!("\"{mode}\" isn't an edit mode, expected emacs or vi"),
+        });
+    };
+
+    let mut config = CONFIG.lock().unwrap();
+    config.as_mut().unwrap().edit_mode = mode;
+
+    println!("{color_green}Edit mode set to {mode}{color_reset}");
+
+    Ok(())
 }
 
 /// Built-in commands
@@ -263,25 +647,69 @@ impl DefaultCommands {
         let help = Command {
             name: "help",
             help_text: Some(
-                "Shows all commands, or info about a specific command if followed by its name",
+                "Shows all commands, or info about a specific command, built-in function, or error code if followed by its name",
             ),
             action: help,
             aliases: None,
             parameter_documentation: Some(ParameterDocumentation::new(
                 vec!["command"],
-                vec!["(Optional) Command to get help for, lists all commands if not specified"],
+                vec!["(Optional) Command, built-in function, or error code (like E008) to get help for, lists all commands if not specified"],
                 vec!["String or Nothing"],
             )),
         };
 
         let show_variables = Command {
             name: "showvariables",
-            help_text: Some("Lists all variables"),
+            help_text: Some("Lists all variables and user-defined functions"),
             action: show_variables,
             aliases: Some(vec!["listvariables", "vars", "showvars", "showv"]),
             parameter_documentation: None,
         };
 
+        let constants = Command {
+            name: "constants",
+            help_text: Some("Lists all built-in mathematical and physical constants by category, with their values and units"),
+            action: constants,
+            aliases: Some(vec!["showconstants", "consts"]),
+            parameter_documentation: None,
+        };
+
+        let shadow = Command {
+            name: "shadow",
+            help_text: Some("Declares a variable that replaces a built-in constant like pi, or ans, until clearvariables is run.\nPlain declarations like pi = 3 aren't allowed so built-ins can't be replaced by accident"),
+            action: shadow,
+            aliases: None,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["declaration"],
+                vec!["Declaration like name = value"],
+                vec!["Declaration"],
+            )),
+        };
+
+        let unset = Command {
+            name: "unset",
+            help_text: Some("Removes user-defined variables and functions. Built-ins can't be removed, but removing a variable that shadows one brings the built-in back"),
+            action: unset,
+            aliases: Some(vec!["delete", "del", "remove"]),
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["names"],
+                vec!["Names or aliases to remove, separated by spaces. * matches any characters and ? matches one, like tmp*"],
+                vec!["Names"],
+            )),
+        };
+
+        let save = Command {
+            name: "save",
+            help_text: Some("Saves user-defined variables and functions to the workspace file, which is loaded when SmartCalc starts.\nThe workspace is also saved on exit unless save_workspace is turned off in the config"),
+            action: save,
+            aliases: None,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["file"],
+                vec!["(Optional) File to save to instead of the workspace file"],
+                vec!["Path or Nothing"],
+            )),
+        };
+
         let show_history = Command {
             name: "showhistory",
             help_text: Some("Shows expression history"),
@@ -316,7 +744,9 @@ impl DefaultCommands {
 
         let clearvariables = Command {
             name: "clearvariables",
-            help_text: Some("Clears user-defined variables"),
+            help_text: Some(
+                "Clears user-defined variables and functions, and restores shadowed built-ins",
+            ),
             action: clear_variables,
             aliases: Some(vec!["clearv", "clearvars"]),
             parameter_documentation: None,
@@ -328,9 +758,9 @@ impl DefaultCommands {
                 "Clears everything (terminal, expression history, and user-defined variables)",
             ),
             action: |_| {
-                clear_variables(None);
-                clear_history(None);
-                clear_terminal(None);
+                clear_variables(None)?;
+                clear_history(None)?;
+                clear_terminal(None)
             },
             aliases: Some(vec!["cleara"]),
             parameter_documentation: None,
@@ -349,16 +779,76 @@ impl DefaultCommands {
         let convert = Command {
             name: "convert",
             help_text: Some(
-                "Converts between number bases.\nNumber base can be specified by prefixing number with 0b for binary, 0o for octal, 0d for decimal, and 0x for hexadecimal.\nIf not specified, SmartCalc will guess"
+                "Converts between number bases, or between units.\nNumber base can be specified by prefixing number with 0b for binary, 0o for octal, 0d for decimal, and 0x for hexadecimal.\nIf not specified, SmartCalc will guess.\nUnits are converted with \"to\" or \"in\", like convert 5 mi to km or convert 100 °F in °C. Conversions also work inside any expression, like 5 mi in km"
             ),
             action: convert,
             aliases: None,
-            parameter_documentation: Some(ParameterDocumentation::new(vec!["number"], vec!["Number to convert"], vec!["Integer"]))
+            parameter_documentation: Some(ParameterDocumentation::new(vec!["number"], vec!["Number to convert, or a quantity followed by \"to\" or \"in\" and a unit"], vec!["Integer or Quantity"]))
+        };
+
+        let functions = Command {
+            name: "functions",
+            help_text: Some("Lists all built-in functions"),
+            action: functions,
+            aliases: Some(vec!["showfunctions", "funcs"]),
+            parameter_documentation: None,
+        };
+
+        let mode = Command {
+            name: "mode",
+            help_text: Some("Shows the angle mode, or sets it to degrees, radians, or gradians.\nAngles can also be given in a specific unit regardless of the mode, like 30deg, 30°, 1.2rad, or 50grad"),
+            action: mode,
+            aliases: Some(vec!["anglemode"]),
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["mode"],
+                vec!["(Optional) Angle mode to switch to, shows the current mode if not specified"],
+                vec!["deg, rad, or grad"],
+            )),
+        };
+
+        let precision = Command {
+            name: "precision",
+            help_text: Some("Shows the precision, or switches decimals between fast floating point and exact calculations.\nIn exact mode, decimals like 0.1 stay exact through + - * /. Divisions of whole numbers like 1/3 are always exact, and are rounded to a chosen number of decimal places when shown"),
+            action: precision,
+            aliases: None,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["setting"],
+                vec!["(Optional) float, exact, or a number of decimal places to show exact results with"],
+                vec!["String, Integer, or Nothing"],
+            )),
+        };
+
+        let fractions = Command {
+            name: "fractions",
+            help_text: Some("Shows or changes how exact results like 7/4 are shown next to their decimal value: as a fraction (7/4), a mixed number (1 3/4), both, or not at all"),
+            action: fractions,
+            aliases: Some(vec!["fractionstyle"]),
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["style"],
+                vec!["(Optional) Style to switch to, shows the current style if not specified"],
+                vec!["none, fraction, mixed, or both"],
+            )),
+        };
+
+        let edit_mode = Command {
+            name: "editmode",
+            help_text: Some("Shows or changes the key bindings for editing the prompt line.\nemacs uses readline's bindings like Ctrl-A and Ctrl-K. vi starts each line in insert mode, and Esc switches to normal mode with h, l, w, b, 0, $, x, dw, cw, u, and k/j for history"),
+            action: edit_mode,
+            aliases: None,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["mode"],
+                vec!["(Optional) Edit mode to switch to, shows the current mode if not specified"],
+                vec!["emacs or vi"],
+            )),
         };
 
         let mut commands = COMMANDS.lock().unwrap();
         commands.insert(help.name.to_owned(), help);
         commands.insert(show_variables.name.to_owned(), show_variables);
+        commands.insert(constants.name.to_owned(), constants);
+        commands.insert(shadow.name.to_owned(), shadow);
+        commands.insert(unset.name.to_owned(), unset);
+        commands.insert(save.name.to_owned(), save);
         commands.insert(show_history.name.to_owned(), show_history);
         commands.insert(exit.name.to_owned(), exit);
         commands.insert(clear.name.to_owned(), clear);
@@ -367,5 +857,10 @@ impl DefaultCommands {
         commands.insert(clearall.name.to_owned(), clearall);
         commands.insert(features.name.to_owned(), features);
         commands.insert(convert.name.to_owned(), convert);
+        commands.insert(functions.name.to_owned(), functions);
+        commands.insert(mode.name.to_owned(), mode);
+        commands.insert(precision.name.to_owned(), precision);
+        commands.insert(fractions.name.to_owned(), fractions);
+        commands.insert(edit_mode.name.to_owned(), edit_mode);
     }
 }
@@ -0,0 +1,622 @@
+//! Built-in math functions
+use std::cmp::Ordering;
+
+use number::Number;
+use parameter_documentation::ParameterDocumentation;
+
+use crate::{
+    angle_mode::AngleMode,
+    builtin_function::{BuiltinFunction, UnitHandling},
+    calc_error::CalcError,
+    *,
+};
+
+/// Built-in functions
+pub struct DefaultFunctions;
+
+impl DefaultFunctions {
+    /// Registers all built-in functions
+    pub fn register() {
+        let sqrt = BuiltinFunction {
+            name: "sqrt",
+            help_text: Some("Square root, complex for negative numbers"),
+            action: |args| {
+                Ok(match &args[0] {
+                    x if x.is_complex() || x.to_f64() < 0.0 => {
+                        Number::from_inexact_complex(x.to_complex().sqrt())
+                    }
+                    x => Number::Float(x.to_f64().sqrt()),
+                })
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Root(2),
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to take the square root of"],
+                vec!["Number"],
+            )),
+        };
+
+        let cbrt = BuiltinFunction {
+            name: "cbrt",
+            help_text: Some("Cube root"),
+            action: |args| Ok(Number::Float(args[0].to_f64().cbrt())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Root(3),
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to take the cube root of"],
+                vec!["Number"],
+            )),
+        };
+
+        let abs = BuiltinFunction {
+            name: "abs",
+            help_text: Some("Absolute value, or magnitude of a complex number"),
+            action: |args| Ok(args[0].abs()),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to take the absolute value of"],
+                vec!["Number"],
+            )),
+        };
+
+        let exp = BuiltinFunction {
+            name: "exp",
+            help_text: Some("e raised to a power"),
+            action: |args| Ok(Number::from_inexact_complex(args[0].to_complex().exp())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Exponent"],
+                vec!["Number"],
+            )),
+        };
+
+        let ln = BuiltinFunction {
+            name: "ln",
+            help_text: Some("Natural logarithm, complex for negative numbers"),
+            action: |args| {
+                Ok(match &args[0] {
+                    x if x.is_complex() || x.to_f64() < 0.0 => {
+                        Number::from_inexact_complex(x.to_complex().ln())
+                    }
+                    x => Number::Float(x.to_f64().ln()),
+                })
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to take the logarithm of"],
+                vec!["Nonzero number"],
+            )),
+        };
+
+        let log10 = BuiltinFunction {
+            name: "log10",
+            help_text: Some("Base 10 logarithm"),
+            action: |args| Ok(Number::Float(args[0].to_f64().log10())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to take the logarithm of"],
+                vec!["Positive number"],
+            )),
+        };
+
+        let log2 = BuiltinFunction {
+            name: "log2",
+            help_text: Some("Base 2 logarithm"),
+            action: |args| Ok(Number::Float(args[0].to_f64().log2())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to take the logarithm of"],
+                vec!["Positive number"],
+            )),
+        };
+
+        let log = BuiltinFunction {
+            name: "log",
+            help_text: Some("Logarithm with any base, base 10 if not specified"),
+            action: |args| {
+                let base = args.get(1).map_or(10.0, Number::to_f64);
+                Ok(Number::Float(args[0].to_f64().log(base)))
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(2),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x", "base"],
+                vec![
+                    "Number to take the logarithm of",
+                    "(Optional) Base of the logarithm, 10 if not specified",
+                ],
+                vec!["Positive number", "Positive number"],
+            )),
+        };
+
+        let sin = BuiltinFunction {
+            name: "sin",
+            help_text: Some("Sine of an angle in the current angle mode"),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().to_radians(args[0].to_f64()).sin(),
+                ))
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Angle, like 30 or 30deg to override the angle mode"],
+                vec!["Number"],
+            )),
+        };
+
+        let cos = BuiltinFunction {
+            name: "cos",
+            help_text: Some("Cosine of an angle in the current angle mode"),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().to_radians(args[0].to_f64()).cos(),
+                ))
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Angle, like 30 or 30deg to override the angle mode"],
+                vec!["Number"],
+            )),
+        };
+
+        let tan = BuiltinFunction {
+            name: "tan",
+            help_text: Some("Tangent of an angle in the current angle mode"),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().to_radians(args[0].to_f64()).tan(),
+                ))
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Angle, like 30 or 30deg to override the angle mode"],
+                vec!["Number"],
+            )),
+        };
+
+        let asin = BuiltinFunction {
+            name: "asin",
+            help_text: Some("Inverse sine, in the current angle mode"),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().from_radians(args[0].to_f64().asin()),
+                ))
+            },
+            aliases: Some(vec!["arcsin"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Sine of the angle"],
+                vec!["Number between -1 and 1"],
+            )),
+        };
+
+        let acos = BuiltinFunction {
+            name: "acos",
+            help_text: Some("Inverse cosine, in the current angle mode"),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().from_radians(args[0].to_f64().acos()),
+                ))
+            },
+            aliases: Some(vec!["arccos"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Cosine of the angle"],
+                vec!["Number between -1 and 1"],
+            )),
+        };
+
+        let atan = BuiltinFunction {
+            name: "atan",
+            help_text: Some("Inverse tangent, in the current angle mode"),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().from_radians(args[0].to_f64().atan()),
+                ))
+            },
+            aliases: Some(vec!["arctan"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Tangent of the angle"],
+                vec!["Number"],
+            )),
+        };
+
+        let atan2 = BuiltinFunction {
+            name: "atan2",
+            help_text: Some(
+                "Angle of the point (x, y) from the positive x axis, in the current angle mode",
+            ),
+            action: |args| {
+                let angle = args[0].to_f64().atan2(args[1].to_f64());
+                Ok(Number::Float(AngleMode::current().from_radians(angle)))
+            },
+            aliases: None,
+            min_arguments: 2,
+            max_arguments: Some(2),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["y", "x"],
+                vec!["Y coordinate", "X coordinate"],
+                vec!["Number", "Number"],
+            )),
+        };
+
+        let sinh = BuiltinFunction {
+            name: "sinh",
+            help_text: Some("Hyperbolic sine"),
+            action: |args| Ok(Number::Float(args[0].to_f64().sinh())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number"],
+                vec!["Number"],
+            )),
+        };
+
+        let cosh = BuiltinFunction {
+            name: "cosh",
+            help_text: Some("Hyperbolic cosine"),
+            action: |args| Ok(Number::Float(args[0].to_f64().cosh())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number"],
+                vec!["Number"],
+            )),
+        };
+
+        let tanh = BuiltinFunction {
+            name: "tanh",
+            help_text: Some("Hyperbolic tangent"),
+            action: |args| Ok(Number::Float(args[0].to_f64().tanh())),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number"],
+                vec!["Number"],
+            )),
+        };
+
+        let floor = BuiltinFunction {
+            name: "floor",
+            help_text: Some("Rounds down to the nearest integer"),
+            action: |args| Ok(args[0].floor()),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to round"],
+                vec!["Number"],
+            )),
+        };
+
+        let ceil = BuiltinFunction {
+            name: "ceil",
+            help_text: Some("Rounds up to the nearest integer"),
+            action: |args| Ok(args[0].ceil()),
+            aliases: Some(vec!["ceiling"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to round"],
+                vec!["Number"],
+            )),
+        };
+
+        let trunc = BuiltinFunction {
+            name: "trunc",
+            help_text: Some("Removes the fractional part of a number"),
+            action: |args| Ok(args[0].trunc()),
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number to truncate"],
+                vec!["Number"],
+            )),
+        };
+
+        let round = BuiltinFunction {
+            name: "round",
+            help_text: Some("Rounds to the nearest integer, or to a number of decimal places"),
+            action: |args| {
+                let decimal_places = args.get(1).map_or(0.0, Number::to_f64);
+                Ok(args[0].round(decimal_places as i32))
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: Some(2),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x", "digits"],
+                vec![
+                    "Number to round",
+                    "(Optional) Decimal places to keep, 0 if not specified",
+                ],
+                vec!["Number", "Integer"],
+            )),
+        };
+
+        let sign = BuiltinFunction {
+            name: "sign",
+            help_text: Some("Sign of a number: -1, 0, or 1"),
+            action: |args| Ok(args[0].signum()),
+            aliases: Some(vec!["sgn"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["x"],
+                vec!["Number"],
+                vec!["Number"],
+            )),
+        };
+
+        let min = BuiltinFunction {
+            name: "min",
+            help_text: Some("Smallest of the given numbers"),
+            action: |args| {
+                Ok(args
+                    .iter()
+                    .min_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                    .unwrap()
+                    .clone())
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: None,
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["values"],
+                vec!["Numbers to compare, separated by commas"],
+                vec!["Numbers"],
+            )),
+        };
+
+        let max = BuiltinFunction {
+            name: "max",
+            help_text: Some("Largest of the given numbers"),
+            action: |args| {
+                Ok(args
+                    .iter()
+                    .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                    .unwrap()
+                    .clone())
+            },
+            aliases: None,
+            min_arguments: 1,
+            max_arguments: None,
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["values"],
+                vec!["Numbers to compare, separated by commas"],
+                vec!["Numbers"],
+            )),
+        };
+
+        let hypot = BuiltinFunction {
+            name: "hypot",
+            help_text: Some("Length of the hypotenuse of a right triangle with the given sides"),
+            action: |args| Ok(Number::Float(args[0].to_f64().hypot(args[1].to_f64()))),
+            aliases: None,
+            min_arguments: 2,
+            max_arguments: Some(2),
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["a", "b"],
+                vec!["Length of the first side", "Length of the second side"],
+                vec!["Number", "Number"],
+            )),
+        };
+
+        let modpow = BuiltinFunction {
+            name: "modpow",
+            help_text: Some("Raises an integer to a power modulo another, exactly and without calculating the full power first"),
+            action: |args| {
+                let error = |message: &str| CalcError::InvalidArgument {
+                    name: "modpow".to_owned(),
+                    message: message.to_owned(),
+                    span: 0..0,
+                };
+
+                if args[2].is_zero() {
+                    return Err(CalcError::DivisionByZero { span: 0..0 });
+                }
+                if args[1].to_f64() < 0.0 {
+                    return Err(error("exponent can't be negative"));
+                }
+
+                args[0]
+                    .modpow(&args[1], &args[2])
+                    .ok_or_else(|| error("arguments have to be integers"))
+            },
+            aliases: Some(vec!["powmod"]),
+            min_arguments: 3,
+            max_arguments: Some(3),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["base", "exponent", "modulus"],
+                vec![
+                    "Integer to raise",
+                    "Power to raise it to, can't be negative",
+                    "Integer to take the remainder by, can't be 0",
+                ],
+                vec!["Integer", "Integer", "Integer"],
+            )),
+        };
+
+        let re = BuiltinFunction {
+            name: "re",
+            help_text: Some("Real part of a complex number"),
+            action: |args| {
+                Ok(match &args[0] {
+                    Number::Complex(value) => Number::Float(value.re),
+                    x => x.clone(),
+                })
+            },
+            aliases: Some(vec!["real"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["z"],
+                vec!["Complex number"],
+                vec!["Number"],
+            )),
+        };
+
+        let im = BuiltinFunction {
+            name: "im",
+            help_text: Some("Imaginary part of a complex number"),
+            action: |args| Ok(Number::Float(args[0].to_complex().im)),
+            aliases: Some(vec!["imag"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["z"],
+                vec!["Complex number"],
+                vec!["Number"],
+            )),
+        };
+
+        let arg = BuiltinFunction {
+            name: "arg",
+            help_text: Some(
+                "Angle of a complex number from the positive real axis, in the current angle mode",
+            ),
+            action: |args| {
+                Ok(Number::Float(
+                    AngleMode::current().from_radians(args[0].to_complex().arg()),
+                ))
+            },
+            aliases: Some(vec!["phase"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Unitless,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["z"],
+                vec!["Complex number"],
+                vec!["Number"],
+            )),
+        };
+
+        let conj = BuiltinFunction {
+            name: "conj",
+            help_text: Some("Complex conjugate, the same number with its imaginary part negated"),
+            action: |args| {
+                Ok(match &args[0] {
+                    Number::Complex(value) => Number::Complex(value.conj()),
+                    x => x.clone(),
+                })
+            },
+            aliases: Some(vec!["conjugate"]),
+            min_arguments: 1,
+            max_arguments: Some(1),
+            units: UnitHandling::Same,
+            parameter_documentation: Some(ParameterDocumentation::new(
+                vec!["z"],
+                vec!["Complex number"],
+                vec!["Number"],
+            )),
+        };
+
+        let mut functions = FUNCTIONS.lock().unwrap();
+        functions.insert(sqrt.name.to_owned(), sqrt);
+        functions.insert(cbrt.name.to_owned(), cbrt);
+        functions.insert(abs.name.to_owned(), abs);
+        functions.insert(exp.name.to_owned(), exp);
+        functions.insert(ln.name.to_owned(), ln);
+        functions.insert(log10.name.to_owned(), log10);
+        functions.insert(log2.name.to_owned(), log2);
+        functions.insert(log.name.to_owned(), log);
+        functions.insert(sin.name.to_owned(), sin);
+        functions.insert(cos.name.to_owned(), cos);
+        functions.insert(tan.name.to_owned(), tan);
+        functions.insert(asin.name.to_owned(), asin);
+        functions.insert(acos.name.to_owned(), acos);
+        functions.insert(atan.name.to_owned(), atan);
+        functions.insert(atan2.name.to_owned(), atan2);
+        functions.insert(sinh.name.to_owned(), sinh);
+        functions.insert(cosh.name.to_owned(), cosh);
+        functions.insert(tanh.name.to_owned(), tanh);
+        functions.insert(floor.name.to_owned(), floor);
+        functions.insert(ceil.name.to_owned(), ceil);
+        functions.insert(trunc.name.to_owned(), trunc);
+        functions.insert(round.name.to_owned(), round);
+        functions.insert(sign.name.to_owned(), sign);
+        functions.insert(min.name.to_owned(), min);
+        functions.insert(max.name.to_owned(), max);
+        functions.insert(hypot.name.to_owned(), hypot);
+        functions.insert(modpow.name.to_owned(), modpow);
+        functions.insert(re.name.to_owned(), re);
+        functions.insert(im.name.to_owned(), im);
+        functions.insert(arg.name.to_owned(), arg);
+        functions.insert(conj.name.to_owned(), conj);
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt::Display;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::CONFIG;
+
+/// Key bindings used for editing the prompt line
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EditMode {
+    /// readline's default bindings, like Ctrl-A and Ctrl-K
+    #[default]
+    #[serde(rename = "emacs")]
+    Emacs,
+    /// Separate insert and normal states, like vim
+    #[serde(rename = "vi")]
+    Vi,
+}
+
+impl Display for EditMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            EditMode::Emacs => "emacs",
+            EditMode::Vi => "vi",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl EditMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "emacs" | "readline" => Some(EditMode::Emacs),
+            "vi" | "vim" => Some(EditMode::Vi),
+            _ => None,
+        }
+    }
+
+    /// Mode currently set in [CONFIG]
+    pub fn current() -> Self {
+        let config = CONFIG.lock().unwrap();
+        config
+            .as_ref()
+            .map(|config| config.edit_mode)
+            .unwrap_or_default()
+    }
+}
+
+/// Whether keys type text or run commands in [EditMode::Vi]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViState {
+    #[default]
+    Insert,
+    Normal,
+}
+
+impl ViState {
+    /// Marker shown at the start of the prompt, which is the same width in both states so the prompt doesn't shift
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            ViState::Insert => "[I]",
+            ViState::Normal => "[N]",
+        }
+    }
+}
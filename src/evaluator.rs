@@ -0,0 +1,632 @@
+//! Calculates the value of a [syntax tree](Node)
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
+
+use num_complex::Complex64;
+use num_traits::Zero;
+
+use crate::{
+    angle_mode::AngleMode,
+    ast::Node,
+    builtin_function::{BuiltinFunction, UnitHandling},
+    calc_error::CalcError,
+    history_entry::HistoryEntry,
+    lexer::Operator,
+    number::Number,
+    precision::Precision,
+    unit::{Dimension, Unit},
+    value::{DisplayUnit, Value},
+    variables::Variables,
+};
+
+/// How deeply function calls can nest before evaluation is stopped, since a function calling itself would never finish
+const MAX_CALL_DEPTH: usize = 64;
+
+pub struct Evaluator<'a> {
+    pub variables: &'a Variables,
+    /// Expression history, most recent entry first
+    pub history: &'a VecDeque<HistoryEntry>,
+    /// Arguments of the function call currently being evaluated, these shadow variables
+    locals: HashMap<String, Value>,
+    depth: usize,
+    /// Decides whether literals are calculated exactly
+    precision: Precision,
+    /// Whether names are looked up as units before variables, for units written after a number or a conversion keyword
+    ///
+    /// This keeps `5 h` meaning 5 hours even though `h` is also Planck's constant
+    units_first: bool,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(variables: &'a Variables, history: &'a VecDeque<HistoryEntry>) -> Self {
+        Self {
+            variables,
+            history,
+            locals: HashMap::new(),
+            depth: 0,
+            precision: Precision::current().0,
+            units_first: false,
+        }
+    }
+
+    pub fn evaluate(&self, node: &Node) -> Result<Value, CalcError> {
+        match node {
+            Node::Number(value) => Ok(Number::from_literal(value, self.precision).into()),
+            Node::Angle(value, unit) => {
+                Ok(convert_angle(Number::from_literal(value, self.precision), *unit).into())
+            }
+            Node::Imaginary(value) => {
+                let value = Number::from_literal(value, Precision::Float).to_f64();
+                Ok(Number::from_complex(Complex64::new(0.0, value)).into())
+            }
+            Node::Variable { name, span } => {
+                if self.units_first {
+                    if let Some(unit) = unit(name) {
+                        return Ok(unit);
+                    }
+                }
+
+                if let Some(value) = self.locals.get(name) {
+                    return Ok(value.clone());
+                }
+
+                if let Some(variable) = self.variables.get(name) {
+                    return Ok(variable.value);
+                }
+
+                // Names that aren't variables can still be units, like `km`
+                unit(name).ok_or_else(|| CalcError::UnknownVariable {
+                    name: name.clone(),
+                    span: span.clone(),
+                })
+            }
+            Node::Call {
+                name,
+                arguments,
+                span,
+            } => self.call(name, arguments, span),
+            Node::LineReference { index, span } => self.line_reference(*index, span),
+            Node::Continuation { span } => self.continuation(span),
+            Node::Negate(operand) => {
+                let value = temperature_difference(self.evaluate(operand)?);
+                Ok(Value {
+                    number: -value.number,
+                    ..value
+                })
+            }
+            Node::Factorial { operand, span } => {
+                let value = self.evaluate(operand)?;
+                unitless(&value, "!", span)?;
+
+                let result = value
+                    .number
+                    .factorial()
+                    .ok_or_else(|| CalcError::InvalidFactorial { span: span.clone() })?;
+                Ok(result.into())
+            }
+            Node::BinaryOperation {
+                operator,
+                span,
+                left,
+                right,
+            } => {
+                let is_unit = is_unit_product(*operator, left);
+                let left = temperature_difference(self.evaluate(left)?);
+
+                // Names multiplying or dividing a quantity are units too, like the h in 5 km/h
+                let right = if is_unit {
+                    self.evaluate_unit(right)?
+                } else {
+                    temperature_difference(self.evaluate(right)?)
+                };
+                binary_operation(*operator, left, right, span)
+            }
+            Node::Quantity { number, unit, span } => {
+                let is_named_unit = matches!(**unit, Node::Variable { .. });
+                let number = self.evaluate(number)?;
+
+                // Angle units can be written apart from the number too, like `30 deg`
+                if let Some(angle_unit) = angle_unit(unit) {
+                    unitless(&number, "an angle unit", span)?;
+                    return Ok(convert_angle(number.number, angle_unit).into());
+                }
+
+                let unit = self.evaluate_unit(unit)?;
+                let quantity = binary_operation(Operator::Multiply, number, unit, span)?;
+
+                // A number written before a unit with an offset is a point on its scale, so 20 °C is 293.15 K rather than 20 K.
+                // Arithmetic turns it back into a difference with [temperature_difference]
+                match &quantity.display_unit {
+                    Some(display_unit) if is_named_unit && !display_unit.offset.is_zero() => {
+                        Ok(Value {
+                            number: quantity.number.clone() + display_unit.offset.clone(),
+                            ..quantity
+                        })
+                    }
+                    _ => Ok(quantity),
+                }
+            }
+            Node::Conversion {
+                value,
+                unit,
+                name,
+                span,
+            } => {
+                let value = self.evaluate(value)?;
+                let unit = self.evaluate_unit(unit)?;
+                same_dimension(&value, &unit, span)?;
+
+                // Differences like 5 °C + 5 °C convert without the offset, so they're 18 °F rather than 50 °F
+                let offset = match unit.display_unit {
+                    Some(unit) if !is_temperature_difference(&value) => unit.offset,
+                    _ => Number::Integer(0.into()),
+                };
+                let display_unit = DisplayUnit {
+                    name: name.clone(),
+                    factor: unit.number,
+                    offset,
+                };
+                Ok(value.with_display_unit(Some(display_unit)))
+            }
+        }
+    }
+
+    /// Evaluates the unit part of a quantity or conversion, looking names up as units first
+    fn evaluate_unit(&self, node: &Node) -> Result<Value, CalcError> {
+        let evaluator = Evaluator {
+            variables: self.variables,
+            history: self.history,
+            locals: self.locals.clone(),
+            depth: self.depth,
+            precision: self.precision,
+            units_first: true,
+        };
+
+        evaluator.evaluate(node)
+    }
+
+    /// Calls a user-defined function, or a [built-in](crate::FUNCTIONS) one if there isn't a user-defined one with the same name
+    fn call(
+        &self,
+        name: &str,
+        arguments: &[Node],
+        span: &Range<usize>,
+    ) -> Result<Value, CalcError> {
+        let Some(function) = self.variables.get_function(name) else {
+            return self.call_builtin(name, arguments, span);
+        };
+
+        if arguments.len() != function.parameters.len() {
+            return Err(CalcError::WrongArgumentCount {
+                name: name.to_owned(),
+                expected: function.parameters.len().to_string(),
+                found: arguments.len(),
+                span: span.clone(),
+            });
+        }
+
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(CalcError::RecursionLimit {
+                name: name.to_owned(),
+                span: span.clone(),
+            });
+        }
+
+        let mut locals = HashMap::new();
+        for (parameter, argument) in function.parameters.iter().zip(arguments) {
+            locals.insert(parameter.clone(), self.evaluate(argument)?);
+        }
+
+        let evaluator = Evaluator {
+            variables: self.variables,
+            history: self.history,
+            locals,
+            depth: self.depth + 1,
+            precision: self.precision,
+            units_first: false,
+        };
+
+        // Spans inside the body don't line up with the expression being calculated, so point at the call instead
+        evaluator
+            .evaluate(&function.body)
+            .map_err(|err| err.at(span.clone()))
+    }
+
+    fn call_builtin(
+        &self,
+        name: &str,
+        arguments: &[Node],
+        span: &Range<usize>,
+    ) -> Result<Value, CalcError> {
+        let Some(function) = BuiltinFunction::find(name) else {
+            return Err(CalcError::UnknownFunction {
+                name: name.to_owned(),
+                span: span.clone(),
+            });
+        };
+
+        if !function.accepts(arguments.len()) {
+            return Err(CalcError::WrongArgumentCount {
+                name: name.to_owned(),
+                expected: function.arity(),
+                found: arguments.len(),
+                span: span.clone(),
+            });
+        }
+
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.evaluate(argument))
+            .collect::<Result<Vec<Value>, CalcError>>()?;
+
+        let dimension = match function.units {
+            UnitHandling::Unitless => {
+                for argument in &arguments {
+                    unitless(argument, name, span)?;
+                }
+
+                Dimension::NONE
+            }
+            UnitHandling::Same => {
+                for argument in &arguments[1..] {
+                    same_dimension(&arguments[0], argument, span)?;
+                }
+
+                arguments[0].dimension
+            }
+            UnitHandling::Root(n) => {
+                arguments[0]
+                    .dimension
+                    .root(n)
+                    .ok_or_else(|| CalcError::InvalidUnit {
+                        message: format!(
+                            "{name} of {} doesn't have a whole unit",
+                            arguments[0].dimension
+                        ),
+                        span: span.clone(),
+                    })?
+            }
+        };
+
+        // Values that all shared a unit keep showing it, and temperatures are measured from its zero so `abs(-40 °C)` is 40 °C
+        let (display_unit, zero) = match function.units {
+            UnitHandling::Same => {
+                let zero = arguments[0]
+                    .display_unit
+                    .as_ref()
+                    .map(|unit| unit.offset.clone())
+                    .filter(|offset| !offset.is_zero());
+                let display_unit = temperature_difference(arguments[0].clone()).display_unit;
+                (display_unit, zero)
+            }
+            _ => (None, None),
+        };
+
+        let numbers: Vec<Number> = arguments
+            .into_iter()
+            .map(|value| match &zero {
+                Some(zero) => value.number - zero.clone(),
+                None => value.number,
+            })
+            .collect();
+        let number = (function.action)(&numbers).map_err(|err| err.at(span.clone()))?;
+        let result = Value::new(number, dimension);
+        Ok(result.with_display_unit(display_unit))
+    }
+
+    /// Line references count from 1, starting at the oldest entry in history
+    fn line_reference(&self, index: usize, span: &Range<usize>) -> Result<Value, CalcError> {
+        let error = |message: String| CalcError::BadLineReference {
+            message,
+            span: span.clone(),
+        };
+
+        if index == 0 {
+            return Err(error("0 is an invalid line index".to_owned()));
+        }
+
+        if index > self.history.len() {
+            return Err(error(
+                "Index given is outside of history's bounds".to_owned(),
+            ));
+        }
+
+        let entry = &self.history[self.history.len() - index];
+        entry.result.clone().ok_or_else(|| {
+            error(format!(
+                "Line {index} (\"{}\") doesn't have a result",
+                entry.expression.trim()
+            ))
+        })
+    }
+
+    fn continuation(&self, span: &Range<usize>) -> Result<Value, CalcError> {
+        self.history
+            .iter()
+            .find_map(|entry| entry.result.clone())
+            .ok_or_else(|| CalcError::NoContinuation { span: span.clone() })
+    }
+}
+
+/// Whether `left operator ...` continues writing a quantity's unit, like `5 kg*m` or `5 km/h`
+fn is_unit_product(operator: Operator, left: &Node) -> bool {
+    if !matches!(operator, Operator::Multiply | Operator::Divide) {
+        return false;
+    }
+
+    match left {
+        Node::Quantity { .. } => true,
+        Node::BinaryOperation { operator, left, .. } => is_unit_product(*operator, left),
+        _ => false,
+    }
+}
+
+/// A temperature like 20 °C as the difference of 20 °C, which is how temperatures are added, multiplied, and negated
+///
+/// Other values are returned as they are
+fn temperature_difference(value: Value) -> Value {
+    match &value.display_unit {
+        Some(unit) if !unit.offset.is_zero() => Value {
+            number: value.number.clone() - unit.offset.clone(),
+            display_unit: Some(DisplayUnit {
+                offset: Number::Integer(0.into()),
+                ..unit.clone()
+            }),
+            ..value
+        },
+        _ => value,
+    }
+}
+
+/// Whether `value` came from [temperature_difference], shown in a unit that has an offset without using it
+fn is_temperature_difference(value: &Value) -> bool {
+    value.display_unit.as_ref().is_some_and(|display_unit| {
+        display_unit.offset.is_zero()
+            && Unit::find(&display_unit.name).is_some_and(|unit| !unit.offset.is_zero())
+    })
+}
+
+/// Angle unit `node` names, like `deg`, unless it's also a unit
+fn angle_unit(node: &Node) -> Option<AngleMode> {
+    match node {
+        Node::Variable { name, .. } if Unit::find(name).is_none() => AngleMode::from_name(name),
+        _ => None,
+    }
+}
+
+/// Converts an angle written in `unit` to the current [AngleMode]
+fn convert_angle(angle: Number, unit: AngleMode) -> Number {
+    let angle_mode = AngleMode::current();
+    if unit == angle_mode {
+        return angle;
+    }
+
+    Number::Float(unit.convert(angle.to_f64(), angle_mode))
+}
+
+/// Looks up a unit by name as a value of 1 of that unit
+fn unit(name: &str) -> Option<Value> {
+    let unit = Unit::find(name)?;
+
+    // Units are always exact, so converting back and forth doesn't drift
+    let factor = Number::from_rational(unit.factor);
+    let display_unit = DisplayUnit {
+        name: name.to_owned(),
+        factor: factor.clone(),
+        offset: Number::from_rational(unit.offset),
+    };
+
+    Some(Value::new(factor, unit.dimension).with_display_unit(Some(display_unit)))
+}
+
+/// Calculates `left operator right`, checking that their units are compatible
+fn binary_operation(
+    operator: Operator,
+    left: Value,
+    right: Value,
+    span: &Range<usize>,
+) -> Result<Value, CalcError> {
+    if matches!(operator, Operator::Divide | Operator::Modulo) && right.number.is_zero() {
+        return Err(CalcError::DivisionByZero { span: span.clone() });
+    }
+
+    // 0 to a negative power is 1 divided by 0
+    let is_negative_power = !right.number.is_complex() && right.number.to_f64() < 0.0;
+    if operator == Operator::Power && left.number.is_zero() && is_negative_power {
+        return Err(CalcError::DivisionByZero { span: span.clone() });
+    }
+
+    // Units are shown the way the values were written as long as the result measures the same thing
+    let (number, dimension, display_unit) = match operator {
+        Operator::Add | Operator::Subtract | Operator::Modulo => {
+            same_dimension(&left, &right, span)?;
+
+            let display_unit = left.display_unit.or(right.display_unit);
+            let number = match operator {
+                Operator::Add => left.number + right.number,
+                Operator::Subtract => left.number - right.number,
+                _ => left.number % right.number,
+            };
+
+            (number, left.dimension, display_unit)
+        }
+        Operator::Multiply => {
+            let display_unit = match (left.has_unit(), right.has_unit()) {
+                (true, false) => left.display_unit,
+                (false, true) => right.display_unit,
+                _ => None,
+            };
+
+            (
+                left.number * right.number,
+                left.dimension * right.dimension,
+                display_unit,
+            )
+        }
+        Operator::Divide => {
+            let display_unit = match right.has_unit() {
+                false => left.display_unit,
+                true => None,
+            };
+
+            (
+                left.number / right.number,
+                left.dimension / right.dimension,
+                display_unit,
+            )
+        }
+        Operator::Power => {
+            unitless(&right, "^", span)?;
+
+            let dimension = if left.has_unit() {
+                let power = right
+                    .number
+                    .to_integer()
+                    .and_then(|power| power.try_into().ok());
+                power
+                    .and_then(|power| left.dimension.pow(power))
+                    .ok_or_else(|| CalcError::InvalidUnit {
+                        message: format!(
+                            "{} can only be raised to small whole powers",
+                            left.dimension
+                        ),
+                        span: span.clone(),
+                    })?
+            } else {
+                Dimension::NONE
+            };
+
+            let number = left
+                .number
+                .pow(&right.number)
+                .ok_or_else(|| CalcError::TooLargeToCalculate { span: span.clone() })?;
+
+            (number, dimension, None)
+        }
+    };
+
+    Ok(Value::new(number, dimension).with_display_unit(display_unit))
+}
+
+/// Errors if `left` and `right` don't measure the same thing
+fn same_dimension(left: &Value, right: &Value, span: &Range<usize>) -> Result<(), CalcError> {
+    if left.dimension == right.dimension {
+        return Ok(());
+    }
+
+    let name = |value: &Value| match value.has_unit() {
+        true => value.dimension.to_string(),
+        false => "(none)".to_owned(),
+    };
+
+    Err(CalcError::IncompatibleUnits {
+        left: name(left),
+        right: name(right),
+        span: span.clone(),
+    })
+}
+
+/// Errors if `value` has a unit, for operations that only work on plain numbers
+fn unitless(value: &Value, operation: &str, span: &Range<usize>) -> Result<(), CalcError> {
+    if !value.has_unit() {
+        return Ok(());
+    }
+
+    Err(CalcError::InvalidUnit {
+        message: format!("{operation} only works on numbers without units"),
+        span: span.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linked_hash_map::LinkedHashMap;
+
+    use crate::{ast, default_functions::DefaultFunctions, lexer};
+
+    /// Calculates `expression` with only the built-in constants and functions, and an empty history
+    fn calculate(expression: &str) -> Result<Value, CalcError> {
+        DefaultFunctions::register();
+
+        // Built directly since the default also moves the terminal's cursor
+        let mut constants = LinkedHashMap::new();
+        Variables::add_basic_constants(&mut constants);
+        let variables = Variables {
+            variables: constants,
+            functions: LinkedHashMap::new(),
+        };
+
+        let tree = ast::build(&lexer::tokenize(expression)?)?;
+        Evaluator::new(&variables, &VecDeque::new()).evaluate(&tree)
+    }
+
+    fn result(expression: &str) -> String {
+        calculate(expression).unwrap().format(None)
+    }
+
+    fn error(expression: &str) -> CalcError {
+        calculate(expression).unwrap_err()
+    }
+
+    #[test]
+    fn exact_results() {
+        assert_eq!(result("1 + 2 * 3"), "7");
+        assert_eq!(result("7 / 2"), "3.5");
+        assert_eq!(result("2^-2"), "0.25");
+        assert_eq!(result("(-2)^2"), "4");
+        assert_eq!(result("2^100"), "1267650600228229401496703205376");
+    }
+
+    #[test]
+    fn division_by_zero() {
+        for expression in ["1 / 0", "5 % 0", "0^-1", "modpow(3, 4, 0)"] {
+            assert_eq!(error(expression).code(), "E011", "{expression}");
+        }
+
+        assert_eq!(error("1 + 2 / 0").span(), Some(6..7));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(error("modpow(3, -1, 5)").code(), "E024");
+        assert_eq!(error("modpow(3.5, 2, 5)").code(), "E024");
+        assert_eq!(error("1 + modpow(3, -1, 5)").span(), Some(4..20));
+        assert_eq!(result("modpow(3, 200, 7)"), "2");
+        assert_eq!(error("sqrt(1, 2)").code(), "E017");
+        assert_eq!(error("sin(5 m)").code(), "E021");
+    }
+
+    #[test]
+    fn too_large_to_calculate() {
+        assert_eq!(error("2^1000000").code(), "E023");
+        assert_eq!(error("(1/3)^1000000").code(), "E023");
+        assert_eq!(result("1^1000000000000"), "1");
+    }
+
+    #[test]
+    fn temperatures() {
+        assert_eq!(result("-40 °C to °F"), "-40 °F");
+        assert_eq!(result("0 °C to K"), "273.15 K");
+        assert_eq!(result("20 °C + 5 °C"), "25 °C");
+        assert_eq!(result("abs(-40 °C)"), "40 °C");
+        assert_eq!(result("max(20 °C, 50 °F)"), "20 °C");
+        assert_eq!(result("min(20 °C, 50 °F)"), "10 °C");
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(result("5 km to m"), "5000 m");
+        assert_eq!(result("max(1 m, 50 cm)"), "1 m");
+        assert_eq!(error("5 m + 2 s").code(), "E020");
+    }
+
+    #[test]
+    fn angle_units() {
+        assert_eq!(result("30 deg"), result("pi / 6"));
+        assert_eq!(result("sin(30 deg)"), result("sin(pi / 6)"));
+        assert_eq!(result("cos(180 °)"), "-1");
+    }
+}
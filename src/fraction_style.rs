@@ -0,0 +1,57 @@
+use std::fmt::Display;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::CONFIG;
+
+/// How exact fractional results are shown next to their decimal value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FractionStyle {
+    /// Only the decimal
+    #[serde(rename = "none")]
+    None,
+    /// A reduced fraction, like `7/4`
+    #[serde(rename = "fraction")]
+    Fraction,
+    /// A mixed number, like `1 3/4`
+    #[serde(rename = "mixed")]
+    Mixed,
+    /// Both a reduced fraction and a mixed number
+    #[default]
+    #[serde(rename = "both")]
+    Both,
+}
+
+impl Display for FractionStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FractionStyle::None => "none",
+            FractionStyle::Fraction => "fraction",
+            FractionStyle::Mixed => "mixed",
+            FractionStyle::Both => "both",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl FractionStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "none" | "off" | "decimal" => Some(FractionStyle::None),
+            "fraction" | "fractions" => Some(FractionStyle::Fraction),
+            "mixed" => Some(FractionStyle::Mixed),
+            "both" | "on" => Some(FractionStyle::Both),
+            _ => None,
+        }
+    }
+
+    /// Style currently set in [CONFIG]
+    pub fn current() -> Self {
+        let config = CONFIG.lock().unwrap();
+        config
+            .as_ref()
+            .map(|config| config.fractions)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,27 @@
+use crate::ast::Node;
+
+/// A user-defined function like `f(x, y) = x^2 + y`
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub name: Box<str>,
+    pub parameters: Vec<String>,
+    pub body: Node,
+    /// The body as the user typed it, for displaying the function
+    pub source: String,
+}
+
+impl Function {
+    pub fn new<S: ToString>(name: S, parameters: Vec<String>, body: Node, source: S) -> Self {
+        Self {
+            name: name.to_string().into_boxed_str(),
+            parameters,
+            body,
+            source: source.to_string(),
+        }
+    }
+
+    /// Signature of the function, like `f(x, y)`
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.parameters.join(", "))
+    }
+}
@@ -0,0 +1,157 @@
+//! Colours the prompt line as it's typed
+use inline_colorization::*;
+
+use crate::{
+    angle_mode::AngleMode,
+    ast::CONVERSION_KEYWORDS,
+    builtin_function::BuiltinFunction,
+    lexer::{self, Token, TokenKind},
+    parser,
+    unit::Unit,
+    variables::Variables,
+    RESET, VARIABLES,
+};
+
+/// `line` with colours added, in the same way it's [parsed](crate::parser::parse)
+///
+/// Comments are green, and a command is magenta with its parameters left plain.
+/// In expressions numbers are cyan, operators yellow, known names blue, unknown names bright red, line references bright cyan, and parentheses without a match get a red background
+pub fn highlight(line: &str) -> String {
+    if line.starts_with("//") || line.starts_with('#') {
+        return format!("{color_green}{line}{color_reset}");
+    }
+
+    if parser::find_command(line).is_some() {
+        let word = line.split(' ').next().unwrap_or_default();
+        return format!("{color_magenta}{word}{color_reset}{}", &line[word.len()..]);
+    }
+
+    match line.split_once('=') {
+        // Names on the left of a declaration are being declared, and a function's parameters can be used on the right
+        Some((name, value)) if !value.contains('=') => {
+            let declared: Vec<String> = tokens(name)
+                .into_iter()
+                .filter_map(|token| match token.kind {
+                    TokenKind::Identifier(name) => Some(name),
+                    _ => None,
+                })
+                .collect();
+
+            format!(
+                "{}{color_yellow}={color_reset}{}",
+                highlight_expression(name, &declared),
+                highlight_expression(value, &declared)
+            )
+        }
+        _ => highlight_expression(line, &[]),
+    }
+}
+
+/// Colours each [token](lexer::tokenize) of `expression`, leaving anything between them plain
+///
+/// `declared` names are always known, even before they exist
+fn highlight_expression(expression: &str, declared: &[String]) -> String {
+    let tokens = tokens(expression);
+    let unmatched = unmatched_parentheses(&tokens);
+    let variables = VARIABLES.lock().unwrap();
+
+    let mut highlighted = String::new();
+    let mut end = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        highlighted.push_str(&expression[end..token.span.start]);
+        end = token.span.end;
+
+        let text = &expression[token.span.clone()];
+        let colour = match &token.kind {
+            TokenKind::Number(_) | TokenKind::Angle(..) | TokenKind::Imaginary(_) => color_cyan,
+            TokenKind::Identifier(name) if CONVERSION_KEYWORDS.contains(&name.as_str()) => {
+                color_yellow
+            }
+            TokenKind::Identifier(name)
+                if declared.contains(name) || is_known_name(&variables, name) =>
+            {
+                color_blue
+            }
+            TokenKind::Identifier(_) => color_bright_red,
+            TokenKind::Operator(_) | TokenKind::Comma | TokenKind::Factorial => color_yellow,
+            TokenKind::LineReference(_) => color_bright_cyan,
+            TokenKind::LeftParen | TokenKind::RightParen if unmatched.contains(&i) => {
+                highlighted.push_str(&format!("{color_white}{bg_red}{text}{RESET}"));
+                continue;
+            }
+            TokenKind::LeftParen | TokenKind::RightParen => {
+                highlighted.push_str(text);
+                continue;
+            }
+        };
+
+        highlighted.push_str(&format!("{colour}{text}{color_reset}"));
+    }
+
+    highlighted.push_str(&expression[end..]);
+    highlighted
+}
+
+/// Tokens of `expression`, skipping over anything the lexer can't read so the rest is still found
+fn tokens(expression: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+
+    while start < expression.len() {
+        let rest = &expression[start..];
+        let error_span = match lexer::tokenize(rest) {
+            Ok(rest_tokens) => {
+                tokens.extend(shifted(rest_tokens, start));
+                break;
+            }
+            Err(err) => err.span().unwrap_or(0..rest.len()),
+        };
+
+        // Everything before the error can be read on its own
+        let error_start = error_span.start.min(rest.len());
+        let before = lexer::tokenize(&rest[..error_start]).unwrap_or_default();
+        tokens.extend(shifted(before, start));
+
+        // Always moves past at least one character, so an error that doesn't cover anything can't repeat forever
+        let skip = rest[error_start..].chars().next().map_or(1, char::len_utf8);
+        start += error_span.end.max(error_start + skip);
+        while start < expression.len() && !expression.is_char_boundary(start) {
+            start += 1;
+        }
+    }
+
+    tokens
+}
+
+fn shifted(tokens: Vec<Token>, offset: usize) -> impl Iterator<Item = Token> {
+    tokens.into_iter().map(move |token| Token {
+        span: token.span.start + offset..token.span.end + offset,
+        ..token
+    })
+}
+
+/// Whether `name` is a variable, function, or unit, including angle units like `deg`
+fn is_known_name(variables: &Variables, name: &str) -> bool {
+    variables.kind(name).is_some()
+        || BuiltinFunction::find(name).is_some()
+        || Unit::find(name).is_some()
+        || AngleMode::from_name(name).is_some()
+}
+
+/// Indices of parentheses in `tokens` that don't have a match
+fn unmatched_parentheses(tokens: &[Token]) -> Vec<usize> {
+    let mut open = Vec::new();
+    let mut unmatched = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::LeftParen => open.push(i),
+            TokenKind::RightParen if open.pop().is_none() => unmatched.push(i),
+            _ => (),
+        }
+    }
+
+    unmatched.extend(open);
+    unmatched
+}
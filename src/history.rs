@@ -0,0 +1,89 @@
+//! Expression history kept between sessions, for recalling with the arrow keys
+use std::{fs, path::PathBuf};
+
+use inline_colorization::*;
+
+use crate::{CONFIG, HISTORY, PAST_HISTORY};
+
+/// Path of the history file, or [None] if saving history is turned off in [CONFIG]
+fn path() -> Option<PathBuf> {
+    let config = CONFIG.lock().unwrap();
+    let config = config.as_ref()?;
+
+    config
+        .save_history
+        .then(|| PathBuf::from(&config.history_file))
+}
+
+/// Reads expressions entered in previous sessions into [PAST_HISTORY], if the history file is turned on and exists
+pub fn load() {
+    let Some(path) = path() else {
+        return;
+    };
+
+    let Ok(raw) = fs::read_to_string(path) else {
+        return;
+    };
+
+    let mut past_history = PAST_HISTORY.lock().unwrap();
+    *past_history = raw
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_owned())
+        .collect();
+}
+
+/// Every expression that can be recalled, most recent first: this session's history followed by previous sessions'
+///
+/// Repeated expressions only show up once if `deduplicate_history` is set in [CONFIG], and there are at most `history_size` entries
+pub fn recall() -> Vec<String> {
+    let config = CONFIG.lock().unwrap();
+    let (size, deduplicate) = config
+        .as_ref()
+        .map(|config| (config.history_size, config.deduplicate_history))
+        .unwrap_or((usize::MAX, false));
+    drop(config);
+
+    let history = HISTORY.lock().unwrap();
+    let past_history = PAST_HISTORY.lock().unwrap();
+
+    let expressions = history
+        .iter()
+        .map(|entry| entry.expression.trim().to_owned())
+        .chain(past_history.iter().cloned());
+
+    let mut entries: Vec<String> = Vec::new();
+    for expression in expressions {
+        if entries.len() == size {
+            break;
+        }
+
+        if !deduplicate || !entries.contains(&expression) {
+            entries.push(expression);
+        }
+    }
+
+    entries
+}
+
+/// Replaces the history file with everything [recallable](recall), oldest first, so the next session starts where this one left off
+///
+/// Does nothing if saving history is turned off. A failed write is reported, but doesn't stop the calculator from exiting
+pub fn save_on_exit() {
+    let Some(path) = path() else {
+        return;
+    };
+
+    let mut entries = recall();
+    entries.reverse();
+
+    let mut contents = entries.join("\n");
+    contents.push('\n');
+    if let Err(err) = fs::write(&path, contents) {
+        println!(
+            "{color_red}Couldn't save history to {}: {err}{color_reset}",
+            path.display()
+        );
+    }
+}
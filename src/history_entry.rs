@@ -0,0 +1,17 @@
+use crate::value::Value;
+
+/// An expression or command the user entered, along with the result if it was a calculation
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub expression: String,
+    pub result: Option<Value>,
+}
+
+impl HistoryEntry {
+    pub fn new<S: ToString>(expression: S, result: Option<Value>) -> Self {
+        Self {
+            expression: expression.to_string(),
+            result,
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! Turns raw input into a flat list of [tokens](Token)
+use std::ops::Range;
+
+use num_rational::BigRational;
+
+use crate::{angle_mode::AngleMode, calc_error::CalcError, number::Number};
+
+/// Binary operators understood by the calculator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    Modulo,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Subtract),
+            '*' => Some(Operator::Multiply),
+            '/' => Some(Operator::Divide),
+            '^' => Some(Operator::Power),
+            '%' => Some(Operator::Modulo),
+            _ => None,
+        }
+    }
+
+    /// Binding power of the operator, higher binds tighter
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Operator::Add | Operator::Subtract => 1,
+            Operator::Multiply | Operator::Divide | Operator::Modulo => 2,
+            Operator::Power => 4,
+        }
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        *self == Operator::Power
+    }
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Power => "^",
+            Operator::Modulo => "%",
+        };
+
+        write!(f, "{symbol}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    /// A number literal, kept exact until the [Precision](crate::precision::Precision) it's calculated with is known
+    Number(BigRational),
+    /// A number with an explicit angle unit, like `30deg` or `30°`
+    Angle(BigRational, AngleMode),
+    /// An imaginary number, like `4i` or `4j`
+    Imaginary(BigRational),
+    Identifier(String),
+    Operator(Operator),
+    LeftParen,
+    RightParen,
+    /// Separates function arguments and parameters
+    Comma,
+    /// Postfix factorial, like `5!`
+    Factorial,
+    /// A reference to the result of a previous line, written as `[n]`
+    LineReference(usize),
+}
+
+impl std::fmt::Display for TokenKind {
+    /// Writes the token back out the way it could have been typed
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Number(value) => write!(f, "{}", Number::from_rational(value.clone())),
+            TokenKind::Angle(value, unit) => {
+                write!(f, "{}{unit}", Number::from_rational(value.clone()))
+            }
+            TokenKind::Imaginary(value) => write!(f, "{}i", Number::from_rational(value.clone())),
+            TokenKind::Identifier(name) => write!(f, "{name}"),
+            TokenKind::Operator(operator) => write!(f, "{operator}"),
+            TokenKind::LeftParen => write!(f, "("),
+            TokenKind::RightParen => write!(f, ")"),
+            TokenKind::Comma => write!(f, ", "),
+            TokenKind::Factorial => write!(f, "!"),
+            TokenKind::LineReference(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Byte range of the token in the original input
+    pub span: Range<usize>,
+}
+
+/// Splits an expression into tokens
+pub fn tokenize(expression: &str) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            let mut seen_exponent = false;
+
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                    continue;
+                }
+
+                // Scientific notation, but only when digits actually follow so `2e` isn't eaten
+                if !seen_exponent && (c == 'e' || c == 'E') {
+                    let rest = &expression[i + 1..];
+                    let digits = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+                    if digits.starts_with(|c: char| c.is_ascii_digit()) {
+                        seen_exponent = true;
+                        let sign_len = rest.len() - digits.len();
+                        for _ in 0..=sign_len {
+                            chars.next();
+                        }
+                        end = i + 1 + sign_len;
+                        continue;
+                    }
+                }
+
+                break;
+            }
+
+            let text = &expression[start..end];
+            let Some(value) = Number::parse_literal(text) else {
+                return Err(CalcError::InvalidNumber {
+                    text: text.to_owned(),
+                    span: start..end,
+                });
+            };
+
+            // Angle unit written directly after the number
+            let suffix: String = expression[end..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '°')
+                .collect();
+
+            if let Some(unit) = AngleMode::from_name(&suffix) {
+                for _ in suffix.chars() {
+                    chars.next();
+                }
+
+                tokens.push(Token {
+                    kind: TokenKind::Angle(value, unit),
+                    span: start..end + suffix.len(),
+                });
+                continue;
+            }
+
+            if suffix == "i" || suffix == "j" {
+                chars.next();
+
+                tokens.push(Token {
+                    kind: TokenKind::Imaginary(value),
+                    span: start..end + 1,
+                });
+                continue;
+            }
+
+            tokens.push(Token {
+                kind: TokenKind::Number(value),
+                span: start..end,
+            });
+            continue;
+        }
+
+        // Temperature units like °C start with a degree sign
+        let is_degree_unit =
+            c == '°' && expression[start + c.len_utf8()..].starts_with(|c: char| c.is_alphabetic());
+
+        // A degree sign on its own is the angle unit, like in `30 °`
+        if c == '°' && !is_degree_unit {
+            chars.next();
+
+            tokens.push(Token {
+                kind: TokenKind::Identifier(c.to_string()),
+                span: start..start + c.len_utf8(),
+            });
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' || is_degree_unit {
+            let mut end = start + c.len_utf8();
+            chars.next();
+
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+
+                end = i + c.len_utf8();
+                chars.next();
+            }
+
+            tokens.push(Token {
+                kind: TokenKind::Identifier(expression[start..end].to_owned()),
+                span: start..end,
+            });
+            continue;
+        }
+
+        if c == '[' {
+            let Some(length) = expression[start..].find(']') else {
+                return Err(CalcError::BadLineReference {
+                    message: "Line reference is missing a closing \"]\"".to_owned(),
+                    span: start..expression.len(),
+                });
+            };
+
+            let end = start + length + 1;
+            let index = expression[start + 1..end - 1].trim();
+
+            // Index NaN
+            let Ok(index_num) = index.parse::<isize>() else {
+                return Err(CalcError::BadLineReference {
+                    message: "Index given for line reference is invalid".to_owned(),
+                    span: start..end,
+                });
+            };
+
+            // Index negative
+            if index_num < 0 {
+                return Err(CalcError::BadLineReference {
+                    message: "Negative numbers are not valid indices for line references"
+                        .to_owned(),
+                    span: start..end,
+                });
+            }
+
+            while chars.peek().is_some_and(|&(i, _)| i < end) {
+                chars.next();
+            }
+
+            tokens.push(Token {
+                kind: TokenKind::LineReference(index_num as usize),
+                span: start..end,
+            });
+            continue;
+        }
+
+        let kind = match c {
+            '(' => TokenKind::LeftParen,
+            ')' => TokenKind::RightParen,
+            ',' => TokenKind::Comma,
+            '!' => TokenKind::Factorial,
+            _ => {
+                let Some(operator) = Operator::from_char(c) else {
+                    return Err(CalcError::UnexpectedCharacter {
+                        character: c,
+                        span: start..start + c.len_utf8(),
+                    });
+                };
+
+                TokenKind::Operator(operator)
+            }
+        };
+
+        chars.next();
+        tokens.push(Token {
+            kind,
+            span: start..start + c.len_utf8(),
+        });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(expression: &str) -> Vec<TokenKind> {
+        tokenize(expression)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    fn number(text: &str) -> TokenKind {
+        TokenKind::Number(Number::parse_literal(text).unwrap())
+    }
+
+    fn identifier(name: &str) -> TokenKind {
+        TokenKind::Identifier(name.to_owned())
+    }
+
+    #[test]
+    fn operators_and_parentheses() {
+        assert_eq!(
+            kinds("(1 + 2) * 3!"),
+            vec![
+                TokenKind::LeftParen,
+                number("1"),
+                TokenKind::Operator(Operator::Add),
+                number("2"),
+                TokenKind::RightParen,
+                TokenKind::Operator(Operator::Multiply),
+                number("3"),
+                TokenKind::Factorial,
+            ]
+        );
+    }
+
+    #[test]
+    fn scientific_notation_needs_digits() {
+        assert_eq!(kinds("2e-3"), vec![number("0.002")]);
+        assert_eq!(kinds("2e"), vec![number("2"), identifier("e")]);
+        assert_eq!(
+            kinds("2e+"),
+            vec![
+                number("2"),
+                identifier("e"),
+                TokenKind::Operator(Operator::Add)
+            ]
+        );
+    }
+
+    #[test]
+    fn suffixes() {
+        assert_eq!(
+            kinds("30deg 4i 5 km"),
+            vec![
+                TokenKind::Angle(Number::parse_literal("30").unwrap(), AngleMode::Degrees),
+                TokenKind::Imaginary(Number::parse_literal("4").unwrap()),
+                number("5"),
+                identifier("km"),
+            ]
+        );
+    }
+
+    #[test]
+    fn degree_signs() {
+        assert_eq!(
+            kinds("-40 °C"),
+            vec![
+                TokenKind::Operator(Operator::Subtract),
+                number("40"),
+                identifier("°C")
+            ]
+        );
+        assert_eq!(kinds("30 °"), vec![number("30"), identifier("°")]);
+        assert_eq!(
+            kinds("30°"),
+            vec![TokenKind::Angle(
+                Number::parse_literal("30").unwrap(),
+                AngleMode::Degrees
+            )]
+        );
+    }
+
+    #[test]
+    fn spans_count_bytes() {
+        let tokens = tokenize("π + 1").unwrap();
+        let spans: Vec<Range<usize>> = tokens.into_iter().map(|token| token.span).collect();
+        assert_eq!(spans, vec![0..2, 3..4, 5..6]);
+    }
+
+    #[test]
+    fn line_references() {
+        assert_eq!(kinds("[2] + 1")[0], TokenKind::LineReference(2));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let err = tokenize("1 + 1.2.3").unwrap_err();
+        assert_eq!(err.code(), "E002");
+        assert_eq!(err.span(), Some(4..9));
+
+        let err = tokenize("2 & 3").unwrap_err();
+        assert_eq!(err.code(), "E003");
+        assert_eq!(err.span(), Some(2..3));
+
+        let err = tokenize("[-1]").unwrap_err();
+        assert_eq!(err.code(), "E009");
+        assert_eq!(err.span(), Some(0..4));
+
+        assert_eq!(tokenize("[1").unwrap_err().code(), "E009");
+    }
+}
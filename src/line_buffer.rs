@@ -0,0 +1,446 @@
+//! Text being typed at the prompt, along with where the cursor is in it
+
+/// Most kills the [KillRing] remembers, like readline
+const MAX_KILLS: usize = 10;
+
+/// An editable line with a cursor, used by [read_line](crate::custom_io::read_line)
+///
+/// The cursor is a byte index that always sits on a character boundary, so multibyte characters like `π` are moved over and deleted as a whole
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LineBuffer {
+    text: String,
+    cursor: usize,
+    /// Text and cursor before each edit, most recent last
+    undo_stack: Vec<(String, usize)>,
+    last_edit: Option<Edit>,
+    /// Where the last yank was inserted, so [yank_pop](LineBuffer::yank_pop) can replace it
+    yanked: Option<usize>,
+}
+
+/// Kinds of edits, to group consecutive edits that are undone or killed together
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edit {
+    /// Typing, consecutive characters are undone together
+    Insert,
+    Delete,
+    /// Consecutive kills are joined into one kill ring entry
+    Kill,
+    Yank,
+    Replace,
+}
+
+/// Text removed with kill commands like Ctrl-K, which can be inserted again with Ctrl-Y
+///
+/// Kept between lines, so text killed in one expression can be yanked into the next
+#[derive(Clone, Debug, Default)]
+pub struct KillRing {
+    /// Oldest kill first
+    kills: Vec<String>,
+    /// Index of the kill that was last yanked, counting back from the most recent
+    yank_index: usize,
+}
+
+impl KillRing {
+    fn push(&mut self, text: String) {
+        self.kills.push(text);
+        if self.kills.len() > MAX_KILLS {
+            self.kills.remove(0);
+        }
+    }
+
+    /// The kill `back` entries before the most recent one, wrapping around
+    fn get(&self, back: usize) -> Option<&String> {
+        let len = self.kills.len();
+        (len > 0).then(|| &self.kills[len - 1 - back % len])
+    }
+}
+
+impl LineBuffer {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Number of characters before the cursor, which is the column it's shown at relative to the start of the line
+    pub fn cursor_column(&self) -> usize {
+        self.text[..self.cursor].chars().count()
+    }
+
+    /// Replaces the whole line, putting the cursor at the end
+    pub fn set(&mut self, text: &str) {
+        self.begin_edit(Edit::Delete);
+        self.text = text.to_owned();
+        self.cursor = self.text.len();
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.begin_edit(Edit::Insert);
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Replaces the text from `start` to the cursor with `text`, like when a name is completed
+    pub fn replace_before_cursor(&mut self, start: usize, text: &str) {
+        self.begin_edit(Edit::Replace);
+        self.text.replace_range(start..self.cursor, text);
+        self.cursor = start + text.len();
+    }
+
+    /// Deletes the character before the cursor, like Backspace
+    pub fn delete_backward(&mut self) {
+        if let Some(start) = self.previous_boundary() {
+            self.begin_edit(Edit::Delete);
+            self.text.drain(start..self.cursor);
+            self.cursor = start;
+        }
+    }
+
+    /// Deletes the character under the cursor, like Delete
+    pub fn delete_forward(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.begin_edit(Edit::Delete);
+            self.text.drain(self.cursor..end);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(start) = self.previous_boundary() {
+            self.move_to(start);
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.move_to(end);
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.move_to(0);
+    }
+
+    pub fn move_end(&mut self) {
+        self.move_to(self.text.len());
+    }
+
+    /// Moves to the start of the current or previous word
+    pub fn move_word_left(&mut self) {
+        self.move_to(self.word_start());
+    }
+
+    /// Moves to the end of the current or next word
+    pub fn move_word_right(&mut self) {
+        self.move_to(self.word_end());
+    }
+
+    /// Moves to the start of the next word, like `w` in vi
+    pub fn move_next_word(&mut self) {
+        self.move_to(self.next_word_start());
+    }
+
+    /// Moves back onto the last character if the cursor is past it, since vi's normal state keeps the cursor on a character
+    pub fn clamp_to_last_character(&mut self) {
+        if self.cursor == self.text.len() {
+            if let Some(start) = self.previous_boundary() {
+                self.cursor = start;
+            }
+        }
+    }
+
+    /// Kills from the cursor to the start of the next word, like `dw` in vi
+    pub fn kill_to_next_word(&mut self, kill_ring: &mut KillRing) {
+        self.kill(self.cursor..self.next_word_start(), kill_ring);
+    }
+
+    /// Kills from the cursor to the end of the line, like Ctrl-K
+    pub fn kill_to_end(&mut self, kill_ring: &mut KillRing) {
+        self.kill(self.cursor..self.text.len(), kill_ring);
+    }
+
+    /// Kills from the start of the line to the cursor, like Ctrl-U
+    pub fn kill_to_start(&mut self, kill_ring: &mut KillRing) {
+        self.kill(0..self.cursor, kill_ring);
+    }
+
+    /// Kills the word before the cursor, like Ctrl-W
+    pub fn kill_word_backward(&mut self, kill_ring: &mut KillRing) {
+        self.kill(self.word_start()..self.cursor, kill_ring);
+    }
+
+    /// Kills the word after the cursor, like Alt-D
+    pub fn kill_word_forward(&mut self, kill_ring: &mut KillRing) {
+        self.kill(self.cursor..self.word_end(), kill_ring);
+    }
+
+    /// Inserts the most recent kill at the cursor, like Ctrl-Y
+    pub fn yank(&mut self, kill_ring: &mut KillRing) {
+        kill_ring.yank_index = 0;
+        if let Some(text) = kill_ring.get(0).cloned() {
+            self.begin_edit(Edit::Yank);
+            self.insert_yank(&text);
+        }
+    }
+
+    /// Replaces the text just yanked with the kill before it, like Alt-Y
+    ///
+    /// Does nothing unless the last edit was a yank
+    pub fn yank_pop(&mut self, kill_ring: &mut KillRing) {
+        let Some(start) = self.yanked.filter(|_| self.last_edit == Some(Edit::Yank)) else {
+            return;
+        };
+
+        kill_ring.yank_index += 1;
+        if let Some(text) = kill_ring.get(kill_ring.yank_index).cloned() {
+            self.text.drain(start..self.cursor);
+            self.cursor = start;
+            self.insert_yank(&text);
+        }
+    }
+
+    /// Undoes the last edit, like Ctrl-_
+    pub fn undo(&mut self) {
+        if let Some((text, cursor)) = self.undo_stack.pop() {
+            self.text = text;
+            self.cursor = cursor;
+        }
+
+        self.last_edit = None;
+    }
+
+    fn insert_yank(&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.yanked = Some(self.cursor);
+        self.cursor += text.len();
+    }
+
+    /// Removes `range` and adds it to the kill ring, joining it onto the last kill if that's what the last edit was
+    fn kill(&mut self, range: std::ops::Range<usize>, kill_ring: &mut KillRing) {
+        if range.is_empty() {
+            return;
+        }
+
+        let joins_last_kill = self.last_edit == Some(Edit::Kill);
+        self.begin_edit(Edit::Kill);
+
+        let killed: String = self.text.drain(range.clone()).collect();
+        match kill_ring.kills.last_mut() {
+            // Killing backwards puts the text before the last kill, so killing two words with Ctrl-W yanks them back in order
+            Some(last) if joins_last_kill && range.end <= self.cursor => {
+                last.insert_str(0, &killed)
+            }
+            Some(last) if joins_last_kill => last.push_str(&killed),
+            _ => kill_ring.push(killed),
+        }
+
+        self.cursor = range.start;
+    }
+
+    /// Saves the line so `edit` can be undone, unless it continues the last edit
+    fn begin_edit(&mut self, edit: Edit) {
+        let continues = edit == Edit::Insert && self.last_edit == Some(Edit::Insert);
+        if !continues {
+            self.undo_stack.push((self.text.clone(), self.cursor));
+        }
+
+        self.last_edit = Some(edit);
+    }
+
+    /// Moves the cursor, which also ends the current group of edits
+    fn move_to(&mut self, cursor: usize) {
+        self.cursor = cursor;
+        self.last_edit = None;
+    }
+
+    /// Byte index of the start of the word before the cursor, skipping anything between words first
+    fn word_start(&self) -> usize {
+        let before = &self.text[..self.cursor];
+        let word_end = before.trim_end_matches(|c| !is_word_character(c)).len();
+
+        before[..word_end].trim_end_matches(is_word_character).len()
+    }
+
+    /// Byte index of the end of the word after the cursor, skipping anything between words first
+    fn word_end(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let word_start = after.len() - after.trim_start_matches(|c| !is_word_character(c)).len();
+        let rest = &after[word_start..];
+
+        self.cursor + word_start + rest.len() - rest.trim_start_matches(is_word_character).len()
+    }
+
+    /// Byte index of the start of the next word, skipping the rest of the current one first
+    fn next_word_start(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let rest = after.trim_start_matches(is_word_character);
+        let rest = if rest.len() == after.len() {
+            // Not in a word, so everything up to the next word is skipped
+            rest.trim_start_matches(|c| !is_word_character(c))
+        } else {
+            rest.trim_start_matches(char::is_whitespace)
+        };
+
+        self.text.len() - rest.len()
+    }
+
+    fn previous_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+    }
+}
+
+/// Whether `c` is part of a word for word movement, which like names in expressions are letters, digits, and underscores
+fn is_word_character(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> LineBuffer {
+        let mut line = LineBuffer::default();
+        for c in text.chars() {
+            line.insert(c);
+        }
+
+        line
+    }
+
+    #[test]
+    fn multibyte_characters_move_as_a_whole() {
+        let mut line = typed("2π");
+        assert_eq!(line.cursor(), 3);
+        assert_eq!(line.cursor_column(), 2);
+
+        line.move_left();
+        assert_eq!(line.cursor(), 1);
+
+        line.delete_forward();
+        assert_eq!(line.text(), "2");
+    }
+
+    #[test]
+    fn word_movement() {
+        let mut line = typed("sqrt(foo_bar) + 1");
+        line.move_word_left();
+        assert_eq!(line.cursor(), 16);
+        line.move_word_left();
+        assert_eq!(line.cursor(), 5);
+
+        line.move_home();
+        line.move_word_right();
+        assert_eq!(line.cursor(), 4);
+        line.move_next_word();
+        assert_eq!(line.cursor(), 5);
+    }
+
+    #[test]
+    fn clamp_to_last_character() {
+        let mut line = typed("12");
+        line.clamp_to_last_character();
+        assert_eq!(line.cursor(), 1);
+        line.clamp_to_last_character();
+        assert_eq!(line.cursor(), 1);
+
+        let mut line = LineBuffer::default();
+        line.clamp_to_last_character();
+        assert_eq!(line.cursor(), 0);
+    }
+
+    #[test]
+    fn kill_and_yank() {
+        let mut kill_ring = KillRing::default();
+        let mut line = typed("1 + 2 + 3");
+        line.move_home();
+        line.move_word_right();
+        line.kill_to_end(&mut kill_ring);
+        assert_eq!(line.text(), "1");
+
+        line.yank(&mut kill_ring);
+        line.yank(&mut kill_ring);
+        assert_eq!(line.text(), "1 + 2 + 3 + 2 + 3");
+    }
+
+    #[test]
+    fn consecutive_kills_join() {
+        let mut kill_ring = KillRing::default();
+        let mut line = typed("a b c");
+        line.kill_word_backward(&mut kill_ring);
+        line.kill_word_backward(&mut kill_ring);
+        assert_eq!(line.text(), "a ");
+
+        line.yank(&mut kill_ring);
+        assert_eq!(line.text(), "a b c");
+    }
+
+    #[test]
+    fn yank_pop_cycles_through_kills() {
+        let mut kill_ring = KillRing::default();
+        let mut line = typed("one");
+        line.kill_to_start(&mut kill_ring);
+        line.set("two");
+        line.kill_to_start(&mut kill_ring);
+
+        line.yank(&mut kill_ring);
+        assert_eq!(line.text(), "two");
+        line.yank_pop(&mut kill_ring);
+        assert_eq!(line.text(), "one");
+        line.yank_pop(&mut kill_ring);
+        assert_eq!(line.text(), "two");
+
+        // Only right after a yank
+        line.insert('!');
+        line.yank_pop(&mut kill_ring);
+        assert_eq!(line.text(), "two!");
+    }
+
+    #[test]
+    fn undo_groups_typing() {
+        let mut kill_ring = KillRing::default();
+        let mut line = typed("12");
+        line.move_left();
+        line.insert('3');
+        line.insert('4');
+        assert_eq!(line.text(), "1342");
+
+        line.kill_to_end(&mut kill_ring);
+        assert_eq!(line.text(), "134");
+
+        line.undo();
+        assert_eq!(line.text(), "1342");
+        assert_eq!(line.cursor(), 3);
+        line.undo();
+        assert_eq!(line.text(), "12");
+        line.undo();
+        assert_eq!(line.text(), "");
+        line.undo();
+        assert_eq!(line.text(), "");
+    }
+
+    #[test]
+    fn replace_before_cursor() {
+        let mut line = typed("1 + sq");
+        line.replace_before_cursor(4, "sqrt");
+        assert_eq!(line.text(), "1 + sqrt");
+        assert_eq!(line.cursor(), 8);
+
+        line.undo();
+        assert_eq!(line.text(), "1 + sq");
+    }
+}
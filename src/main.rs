@@ -6,37 +6,64 @@ use std::{
     sync::Mutex,
 };
 
+use angle_mode::AngleMode;
+use builtin_function::BuiltinFunction;
 use command::Command;
 use config::Config;
+use edit_mode::{EditMode, ViState};
+use history_entry::HistoryEntry;
 use inline_colorization::*;
+use line_buffer::KillRing;
+use number::MAX_DIGITS;
 
 use lazy_static::lazy_static;
 use parser::parse;
 use variables::Variables;
 
+pub mod angle_mode;
+pub mod ast;
+pub mod builtin_function;
+pub mod calc_error;
 pub mod command;
+pub mod completion;
 pub mod config;
+pub mod constants;
 pub mod custom_io;
 pub mod default_commands;
-pub mod invalid_expression;
+pub mod default_functions;
+pub mod edit_mode;
+pub mod evaluator;
+pub mod fraction_style;
+pub mod function;
+pub mod highlight;
+pub mod history;
+pub mod history_entry;
+pub mod lexer;
+pub mod line_buffer;
+pub mod number;
 pub mod parameter_documentation;
-pub mod parse_error;
 pub mod parser;
+pub mod precision;
+pub mod unit;
+pub mod value;
 pub mod variable;
 pub mod variables;
 #[cfg(windows)]
 mod windows;
+pub mod workspace;
 
 const ITALIC: &str = "\x1b[3m";
 const RESET: &str = "\x1b[0m";
 
 lazy_static! {
     pub static ref VARIABLES: Mutex<Variables> = Mutex::new(Variables::default());
-    pub static ref BUILTIN_VARIABLE_COUNT: Mutex<usize> = Mutex::new(0);
-    pub static ref HISTORY: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
-    pub static ref OPERATORS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    pub static ref HISTORY: Mutex<VecDeque<HistoryEntry>> = Mutex::new(VecDeque::new());
+    /// Expressions from previous sessions, most recent first, which can be recalled but not referenced with `[n]`
+    pub static ref PAST_HISTORY: Mutex<Vec<String>> = Mutex::new(Vec::new());
     pub static ref COMMANDS: Mutex<HashMap<String, Command>> = Mutex::new(HashMap::new());
+    pub static ref FUNCTIONS: Mutex<HashMap<String, BuiltinFunction>> = Mutex::new(HashMap::new());
     pub static ref CONFIG: Mutex<Option<Config>> = Mutex::new(None);
+    pub static ref KILL_RING: Mutex<KillRing> = Mutex::new(KillRing::default());
 }
 
 fn main() {
@@ -46,8 +73,8 @@ fn main() {
         windows::enable_vt_processing();
     }
 
-    setup_default_operators();
     default_commands::DefaultCommands::register();
+    default_functions::DefaultFunctions::register();
 
     let mut config = CONFIG.lock().unwrap();
     *config = Some(read_config());
@@ -55,15 +82,13 @@ fn main() {
     // Initialize VARIABLES early
     let variables = VARIABLES.lock().unwrap();
 
-    // Get number of built-in variables for math later
-    let mut builtin_var_count = BUILTIN_VARIABLE_COUNT.lock().unwrap();
-    *builtin_var_count = variables.variables.len();
-
     drop(config);
-    drop(builtin_var_count);
     drop(variables);
 
-    default_commands::clear_terminal(None);
+    _ = default_commands::clear_terminal(None);
+
+    workspace::load();
+    history::load();
 
     let input = &mut String::new();
     loop {
@@ -71,9 +96,17 @@ fn main() {
         let count = history.len();
         drop(history);
 
+        let angle_mode = AngleMode::current();
+
+        // Vi mode starts every line in insert mode
+        let vi_state = match EditMode::current() {
+            EditMode::Vi => format!("{color_yellow}{} ", ViState::Insert.indicator()),
+            EditMode::Emacs => "".to_owned(),
+        };
+
         input.clear();
         print!(
-            "{color_blue}[{color_cyan}{}{color_blue}]> {color_reset}",
+            "{vi_state}{color_magenta}{angle_mode} {color_blue}[{color_cyan}{}{color_blue}]> {color_reset}",
             count + 1
         );
         io::stdout().flush().unwrap();
@@ -81,7 +114,11 @@ fn main() {
         *input = custom_io::read_line().unwrap();
         let result = parse(input.clone());
         if let Err(err) = result {
-            println!("{color_red}{err}{color_reset}");
+            if let Some(underline) = err.underline(input.trim_end_matches('\n')) {
+                println!("{underline}");
+            }
+
+            println!("{color_red}{}: {err}{color_reset}", err.code());
         }
     }
 }
@@ -89,9 +126,7 @@ fn main() {
 fn read_config() -> Config {
     let path = PathBuf::from_str("./config.toml").unwrap();
     if !path.exists() {
-        let config = Config {
-            time_expression: false,
-        };
+        let config = Config::default();
 
         _ = std::fs::write(path, toml::to_string(&config).unwrap());
         config
@@ -100,13 +135,16 @@ fn read_config() -> Config {
 
         let result = toml::from_str::<Config>(&raw);
 
-        if let Ok(result) = result {
+        if let Ok(mut result) = result {
+            if result.digits.is_some_and(|digits| digits > MAX_DIGITS) {
+                println!("{color_red}Config digits can be at most {MAX_DIGITS}, using {MAX_DIGITS}{color_reset}");
+                result.digits = Some(MAX_DIGITS);
+            }
+
             result
         } else {
             println!("{color_red}Config is invalid, running with default config{color_reset}");
-            Config {
-                time_expression: false,
-            }
+            Config::default()
         }
     }
 }
@@ -116,11 +154,3 @@ fn splash() {
         "{color_blue}SmartCalc{color_reset}\n{ITALIC}Type \"help\" for a list of commands{RESET}\n",
     );
 }
-
-fn setup_default_operators() {
-    let mut lock = OPERATORS.lock().unwrap();
-    *lock = vec!["+", "-", "*", "/", "^", "=", "%", "^"]
-        .into_iter()
-        .map(String::from)
-        .collect();
-}
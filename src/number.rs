@@ -0,0 +1,701 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+use crate::{angle_mode::AngleMode, fraction_style::FractionStyle, precision::Precision};
+
+/// Largest result, in bits, that `^` calculates exactly, anything larger is an error rather than losing precision
+const MAX_EXACT_POWER_BITS: u64 = 1_000_000;
+
+/// Largest number `!` is calculated for, since the result grows very quickly
+pub const MAX_FACTORIAL: u32 = 10_000;
+
+/// Decimal places exact results are shown with if no other number is set
+pub const DEFAULT_DIGITS: usize = 16;
+
+/// Most decimal places numbers are shown or rounded with, since every extra place makes the number bigger to work with
+pub const MAX_DIGITS: usize = 1000;
+
+/// Largest power of ten allowed in a literal like `1e300`
+const MAX_LITERAL_EXPONENT: i32 = 10_000;
+
+/// A value calculated by SmartCalc
+///
+/// Whole numbers are always [integers](Number::Integer), and dividing them gives an exact [rational](Number::Rational).
+/// Decimal literals depend on the [Precision] in use, and mixing a float into a calculation makes the result a float
+#[derive(Clone, Debug)]
+pub enum Number {
+    Float(f64),
+    /// A whole number of any size
+    Integer(BigInt),
+    Rational(BigRational),
+    /// A number with a nonzero imaginary part, always calculated with floats
+    Complex(Complex64),
+}
+
+impl Number {
+    /// Parses a decimal literal like `1.5` or `2e-3`, without losing any precision
+    pub fn parse_literal(text: &str) -> Option<BigRational> {
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(i) => (&text[..i], text[i + 1..].parse::<i32>().ok()?),
+            None => (text, 0),
+        };
+
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = format!("{whole}{fraction}");
+        if digits.is_empty()
+            || !digits.chars().all(|c| c.is_ascii_digit())
+            || exponent.abs() > MAX_LITERAL_EXPONENT
+        {
+            return None;
+        }
+
+        let digits: BigInt = digits.parse().ok()?;
+        let scale = exponent - fraction.len() as i32;
+        let ten = BigInt::from(10);
+
+        Some(if scale >= 0 {
+            BigRational::from_integer(digits * ten.pow(scale as u32))
+        } else {
+            BigRational::new(digits, ten.pow(scale.unsigned_abs()))
+        })
+    }
+
+    /// Turns a parsed literal into a number using the given precision
+    pub fn from_literal(literal: &BigRational, precision: Precision) -> Self {
+        match precision {
+            Precision::Float if !literal.is_integer() => {
+                Number::Float(literal.to_f64().unwrap_or(f64::NAN))
+            }
+            _ => Number::from_rational(literal.clone()),
+        }
+    }
+
+    /// Wraps an exact value, as an [integer](Number::Integer) if it's whole
+    pub fn from_rational(value: BigRational) -> Self {
+        if value.is_integer() {
+            Number::Integer(value.to_integer())
+        } else {
+            Number::Rational(value)
+        }
+    }
+
+    /// Wraps a complex number, as a [float](Number::Float) if it has no imaginary part
+    pub fn from_complex(value: Complex64) -> Self {
+        if value.im == 0.0 {
+            Number::Float(value.re)
+        } else {
+            Number::Complex(value)
+        }
+    }
+
+    /// Like [from_complex](Number::from_complex), but zeroes a part that's negligible next to the other one
+    ///
+    /// Transcendental functions leave rounding noise in parts that should be zero, so `(-4)^0.5` would be `1.2e-16 + 2i` instead of `2i`
+    pub fn from_inexact_complex(value: Complex64) -> Self {
+        let noise = value.norm() * f64::EPSILON * 4.0;
+        let clean = |part: f64| if part.abs() < noise { 0.0 } else { part };
+        Number::from_complex(Complex64::new(clean(value.re), clean(value.im)))
+    }
+
+    pub fn to_complex(&self) -> Complex64 {
+        match self {
+            Number::Complex(value) => *value,
+            _ => Complex64::new(self.to_f64(), 0.0),
+        }
+    }
+
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Number::Complex(_))
+    }
+
+    /// The exact value of the number, or [None] if it's a float
+    fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Number::Float(_) | Number::Complex(_) => None,
+            Number::Integer(value) => Some(BigRational::from_integer(value.clone())),
+            Number::Rational(value) => Some(value.clone()),
+        }
+    }
+
+    /// The number as an integer, or [None] if it isn't a whole number
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Number::Float(value) if value.fract() == 0.0 => BigInt::from_f64(*value),
+            Number::Float(_) | Number::Complex(_) => None,
+            Number::Integer(value) => Some(value.clone()),
+            Number::Rational(value) => value.is_integer().then(|| value.to_integer()),
+        }
+    }
+
+    /// The number as a float, complex numbers are [NaN](f64::NAN) since they don't have a real value
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Float(value) => *value,
+            Number::Complex(_) => f64::NAN,
+            Number::Integer(value) => value.to_f64().unwrap_or(f64::NAN),
+            Number::Rational(value) => value.to_f64().unwrap_or(f64::NAN),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Float(value) => *value == 0.0,
+            Number::Integer(value) => value.is_zero(),
+            Number::Rational(value) => value.is_zero(),
+            Number::Complex(value) => value.is_zero(),
+        }
+    }
+
+    /// `self` to the power of `exponent`, or [None] if an exact result would be more than [MAX_EXACT_POWER_BITS] bits
+    pub fn pow(&self, exponent: &Number) -> Option<Number> {
+        if let (Some(base), Number::Integer(exponent)) = (self.to_rational(), exponent) {
+            let bits = base.numer().bits().max(base.denom().bits());
+            let exponent = match exponent.to_i32() {
+                Some(exponent) => Some(exponent),
+                // 0, 1, and -1 stay the same size to any power, so only the exponent's sign and whether it's odd matter
+                None if bits <= 1 => Some(
+                    if (exponent % BigInt::from(2)).is_zero() {
+                        2
+                    } else {
+                        1
+                    } * exponent.signum().to_i32()?,
+                ),
+                None => None,
+            };
+
+            let small_exponent = exponent
+                .filter(|e| bits.saturating_mul(e.unsigned_abs() as u64) <= MAX_EXACT_POWER_BITS);
+
+            match small_exponent {
+                // 0 to a negative power would divide by zero
+                Some(exponent) if !(base.is_zero() && exponent < 0) => {
+                    return Some(Number::from_rational(base.pow(exponent)));
+                }
+                None => return None,
+                _ => (),
+            }
+        }
+
+        // Fractional powers of negative numbers are complex, like `(-4)^0.5`
+        if self.is_complex()
+            || exponent.is_complex()
+            || (self.to_f64() < 0.0 && exponent.to_f64().fract() != 0.0)
+        {
+            return Some(Number::from_inexact_complex(
+                self.to_complex().powc(exponent.to_complex()),
+            ));
+        }
+
+        Some(Number::Float(self.to_f64().powf(exponent.to_f64())))
+    }
+
+    /// Product of every whole number from 1 up to this one, or [None] if this isn't a whole number from 0 to [MAX_FACTORIAL]
+    pub fn factorial(&self) -> Option<Number> {
+        let n = self
+            .to_integer()?
+            .to_u32()
+            .filter(|n| *n <= MAX_FACTORIAL)?;
+        Some(Number::Integer((1..=n).map(BigInt::from).product()))
+    }
+
+    /// `self` to the power of `exponent`, modulo `modulus`, or [None] if any of them aren't whole numbers,
+    /// `exponent` is negative, or `modulus` is zero
+    pub fn modpow(&self, exponent: &Number, modulus: &Number) -> Option<Number> {
+        let exponent = exponent.to_integer().filter(|e| !e.is_negative())?;
+        let modulus = modulus.to_integer().filter(|m| !m.is_zero())?;
+        Some(Number::Integer(
+            self.to_integer()?.modpow(&exponent, &modulus),
+        ))
+    }
+
+    pub fn abs(&self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(value.abs()),
+            Number::Integer(value) => Number::Integer(value.abs()),
+            Number::Rational(value) => Number::Rational(value.abs()),
+            Number::Complex(value) => Number::Float(value.norm()),
+        }
+    }
+
+    pub fn floor(&self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(value.floor()),
+            Number::Integer(_) => self.clone(),
+            Number::Rational(value) => Number::from_rational(value.floor()),
+            Number::Complex(value) => {
+                Number::from_complex(Complex64::new(value.re.floor(), value.im.floor()))
+            }
+        }
+    }
+
+    pub fn ceil(&self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(value.ceil()),
+            Number::Integer(_) => self.clone(),
+            Number::Rational(value) => Number::from_rational(value.ceil()),
+            Number::Complex(value) => {
+                Number::from_complex(Complex64::new(value.re.ceil(), value.im.ceil()))
+            }
+        }
+    }
+
+    pub fn trunc(&self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(value.trunc()),
+            Number::Integer(_) => self.clone(),
+            Number::Rational(value) => Number::from_rational(value.trunc()),
+            Number::Complex(value) => {
+                Number::from_complex(Complex64::new(value.re.trunc(), value.im.trunc()))
+            }
+        }
+    }
+
+    /// Rounds half away from zero to `decimal_places`, which is limited to [MAX_DIGITS] either way
+    pub fn round(&self, decimal_places: i32) -> Number {
+        let max_digits = i32::try_from(MAX_DIGITS).unwrap_or(i32::MAX);
+        let decimal_places = decimal_places.clamp(-max_digits, max_digits);
+
+        match self {
+            Number::Float(value) => {
+                let factor = 10f64.powi(decimal_places);
+
+                // Past the range of a float, rounding keeps either every digit or none of them
+                if factor.is_infinite() {
+                    return self.clone();
+                } else if factor == 0.0 {
+                    return Number::Float(0.0);
+                }
+
+                Number::Float((value * factor).round() / factor)
+            }
+            Number::Integer(_) if decimal_places >= 0 => self.clone(),
+            Number::Complex(value) => Number::from_complex(Complex64::new(
+                Number::Float(value.re).round(decimal_places).to_f64(),
+                Number::Float(value.im).round(decimal_places).to_f64(),
+            )),
+            _ => {
+                let value = self.to_rational().unwrap();
+                let factor = BigRational::from_integer(BigInt::from(10)).pow(decimal_places);
+                Number::from_rational((value * &factor).round() / factor)
+            }
+        }
+    }
+
+    /// -1, 0, or 1 depending on the sign of the number, or the point on the unit circle in the same direction for complex numbers
+    pub fn signum(&self) -> Number {
+        match self {
+            Number::Float(value) if *value == 0.0 => Number::Float(0.0),
+            Number::Float(value) => Number::Float(value.signum()),
+            Number::Integer(value) => Number::Integer(value.signum()),
+            Number::Rational(value) => Number::Integer(value.signum().to_integer()),
+            Number::Complex(value) => Number::from_complex(value / value.norm()),
+        }
+    }
+
+    /// Formats the number as a decimal for showing a result
+    ///
+    /// Exact results that don't fit in `digits` decimal places ([DEFAULT_DIGITS] if [None]) are rounded and marked with `…`
+    pub fn format(&self, digits: Option<usize>) -> String {
+        let value = match self {
+            Number::Rational(value) => value,
+            _ => return self.to_string(),
+        };
+
+        let digits = digits.unwrap_or(DEFAULT_DIGITS);
+        match decimal_places(value) {
+            Some(places) if places <= digits => to_decimal(value, places),
+            _ => format!("{}…", to_decimal(value, digits)),
+        }
+    }
+
+    /// Formats a complex number in polar form, like `5∠0.9273rad`, with the angle in the current [AngleMode]
+    ///
+    /// Returns [None] for real numbers
+    pub fn format_polar(&self) -> Option<String> {
+        let Number::Complex(value) = self else {
+            return None;
+        };
+
+        let angle_mode = AngleMode::current();
+        let (radius, angle) = value.to_polar();
+        Some(format!(
+            "{radius}∠{}{angle_mode}",
+            angle_mode.from_radians(angle)
+        ))
+    }
+
+    /// Formats a rational as a reduced fraction and/or mixed number, like `7/4 = 1 3/4`
+    ///
+    /// Returns [None] for whole numbers and floats, or if `style` is [FractionStyle::None]
+    pub fn format_fraction(&self, style: FractionStyle) -> Option<String> {
+        let Number::Rational(value) = self else {
+            return None;
+        };
+
+        let fraction = format!("{}/{}", value.numer(), value.denom());
+
+        // A mixed number is just the fraction again if there's no whole part
+        let whole = value.trunc().to_integer();
+        let mixed = (!whole.is_zero())
+            .then(|| format!("{whole} {}/{}", value.fract().numer().abs(), value.denom()));
+
+        match (style, mixed) {
+            (FractionStyle::None, _) => None,
+            (FractionStyle::Mixed, Some(mixed)) => Some(mixed),
+            (FractionStyle::Both, Some(mixed)) => Some(format!("{fraction} = {mixed}")),
+            _ => Some(fraction),
+        }
+    }
+}
+
+/// Writes a complex number in rectangular form, like `3 + 4i`, with each part written by `part`
+fn write_complex(
+    f: &mut std::fmt::Formatter<'_>,
+    value: &Complex64,
+    part: impl Fn(f64) -> String,
+) -> std::fmt::Result {
+    let imaginary = match value.im.abs() {
+        1.0 => "i".to_owned(),
+        im => format!("{}i", part(im)),
+    };
+
+    match (value.re, value.im < 0.0) {
+        (0.0, false) => write!(f, "{imaginary}"),
+        (0.0, true) => write!(f, "-{imaginary}"),
+        (re, false) => write!(f, "{} + {imaginary}", part(re)),
+        (re, true) => write!(f, "{} - {imaginary}", part(re)),
+    }
+}
+
+/// Number of decimal places needed to write `value` exactly, or [None] if its decimal expansion repeats forever or needs more than [MAX_DIGITS] places
+fn decimal_places(value: &BigRational) -> Option<usize> {
+    let twos = value.denom().trailing_zeros().unwrap_or(0);
+    if twos > MAX_DIGITS as u64 {
+        return None;
+    }
+
+    let mut denominator = value.denom() >> twos;
+    let mut fives = 0;
+    let five = BigInt::from(5);
+
+    // Stops early so huge denominators like 5^100000 don't take thousands of divisions
+    while (&denominator % &five).is_zero() && fives <= MAX_DIGITS {
+        denominator /= &five;
+        fives += 1;
+    }
+
+    if denominator.is_one() {
+        Some(usize::max(twos as usize, fives)).filter(|places| *places <= MAX_DIGITS)
+    } else {
+        None
+    }
+}
+
+/// Writes `value` as a decimal rounded to `places` decimal places, at most [MAX_DIGITS], without trailing zeros
+fn to_decimal(value: &BigRational, places: usize) -> String {
+    let places = places.min(MAX_DIGITS);
+    let exponent = u32::try_from(places).unwrap_or(u32::MAX);
+    let scale = BigRational::from_integer(BigInt::from(10).pow(exponent));
+    let mut digits = (value.abs() * scale).round().to_integer().to_string();
+
+    if places > 0 {
+        if digits.len() <= places {
+            digits = format!("{}{digits}", "0".repeat(places + 1 - digits.len()));
+        }
+
+        digits.insert(digits.len() - places, '.');
+        digits = digits
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_owned();
+    }
+
+    if value.is_negative() && digits.chars().any(|c| c != '0' && c != '.') {
+        digits.insert(0, '-');
+    }
+
+    digits
+}
+
+impl Display for Number {
+    /// Floats and integers are shown as usual, rationals are shown as decimals with up to [DEFAULT_DIGITS] decimal places
+    ///
+    /// If a precision is given (like `{:.25}`), exact numbers are rounded to that many decimal places instead
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self, f.precision()) {
+            (Number::Float(value), Some(precision)) => write!(f, "{value:.precision$}"),
+            (Number::Float(value), None) => write!(f, "{}", format_float(*value)),
+            (Number::Integer(value), _) => write!(f, "{value}"),
+            (Number::Rational(value), Some(precision)) => {
+                write!(f, "{}", to_decimal(value, precision))
+            }
+            (Number::Rational(_), None) => write!(f, "{}", self.format(None)),
+            (Number::Complex(value), Some(precision)) => {
+                write_complex(f, value, |part| format!("{part:.precision$}"))
+            }
+            (Number::Complex(value), None) => write_complex(f, value, |part| part.to_string()),
+        }
+    }
+}
+
+/// Formats a float, in scientific notation if it's too small or large to read as a plain decimal
+fn format_float(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && magnitude.is_finite() && !(1e-7..1e21).contains(&magnitude) {
+        format!("{value:e}")
+    } else {
+        value.to_string()
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Integer(a), Number::Integer(b)) => a.partial_cmp(b),
+            // Complex numbers aren't ordered, they can only be equal
+            (a, b) if a.is_complex() || b.is_complex() => {
+                (a.to_complex() == b.to_complex()).then_some(Ordering::Equal)
+            }
+            (a, b) => match (a.to_rational(), b.to_rational()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => a.to_f64().partial_cmp(&b.to_f64()),
+            },
+        }
+    }
+}
+
+/// Implements an arithmetic operator that's exact for integers and rationals, and falls back to floats or complex numbers otherwise
+macro_rules! impl_operator {
+    ($trait:ident, $method:ident, $operator:tt) => {
+        impl $trait for Number {
+            type Output = Number;
+
+            fn $method(self, rhs: Number) -> Number {
+                match (self, rhs) {
+                    (Number::Integer(a), Number::Integer(b)) => Number::Integer(a $operator b),
+                    (a, b) if a.is_complex() || b.is_complex() => {
+                        Number::from_complex(a.to_complex() $operator b.to_complex())
+                    }
+                    (a, b) => match (a.to_rational(), b.to_rational()) {
+                        (Some(a), Some(b)) => Number::from_rational(a $operator b),
+                        _ => Number::Float(a.to_f64() $operator b.to_f64()),
+                    },
+                }
+            }
+        }
+    };
+}
+
+impl_operator!(Add, add, +);
+impl_operator!(Sub, sub, -);
+impl_operator!(Mul, mul, *);
+// `BigInt` and `Ratio`'s remainders truncate like `f64`'s, so `-7 % 3` is `-1` either way
+impl_operator!(Rem, rem, %);
+
+impl Div for Number {
+    type Output = Number;
+
+    /// Dividing exact numbers is always exact, even for integers that don't divide evenly
+    fn div(self, rhs: Number) -> Number {
+        if self.is_complex() || rhs.is_complex() {
+            return Number::from_complex(self.to_complex() / rhs.to_complex());
+        }
+
+        match (self.to_rational(), rhs.to_rational()) {
+            (Some(a), Some(b)) => Number::from_rational(a / b),
+            _ => Number::Float(self.to_f64() / rhs.to_f64()),
+        }
+    }
+}
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(-value),
+            Number::Integer(value) => Number::Integer(-value),
+            Number::Rational(value) => Number::Rational(-value),
+            Number::Complex(value) => Number::Complex(-value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exact(text: &str) -> Number {
+        Number::from_rational(Number::parse_literal(text).unwrap())
+    }
+
+    fn integer(value: i64) -> Number {
+        Number::Integer(BigInt::from(value))
+    }
+
+    fn fraction(numerator: i64, denominator: i64) -> Number {
+        Number::from_rational(BigRational::new(numerator.into(), denominator.into()))
+    }
+
+    #[test]
+    fn literals_are_exact() {
+        assert_eq!(
+            Number::parse_literal("1.5"),
+            Some(BigRational::new(3.into(), 2.into()))
+        );
+        assert_eq!(
+            Number::parse_literal("2e3"),
+            Some(BigRational::from_integer(2000.into()))
+        );
+        assert_eq!(
+            Number::parse_literal(".5"),
+            Some(BigRational::new(1.into(), 2.into()))
+        );
+        assert_eq!(Number::parse_literal("1.2.3"), None);
+        assert_eq!(Number::parse_literal("1e99999"), None);
+    }
+
+    #[test]
+    fn float_precision_only_affects_fractions() {
+        assert!(matches!(
+            Number::from_literal(&Number::parse_literal("0.1").unwrap(), Precision::Float),
+            Number::Float(_)
+        ));
+        assert!(matches!(
+            Number::from_literal(&Number::parse_literal("3").unwrap(), Precision::Float),
+            Number::Integer(_)
+        ));
+        assert!(matches!(
+            Number::from_literal(&Number::parse_literal("0.1").unwrap(), Precision::Exact),
+            Number::Rational(_)
+        ));
+    }
+
+    #[test]
+    fn exact_arithmetic_stays_exact() {
+        let sum = exact("0.1") + exact("0.2");
+        assert!(matches!(sum, Number::Rational(_)));
+        assert_eq!(sum, exact("0.3"));
+
+        let quotient = integer(7) / integer(2);
+        assert!(matches!(quotient, Number::Rational(_)));
+        assert_eq!(quotient, fraction(7, 2));
+
+        assert!(matches!(integer(6) / integer(3), Number::Integer(_)));
+        assert!(matches!(
+            fraction(1, 2) + fraction(1, 2),
+            Number::Integer(_)
+        ));
+        assert!(matches!(integer(1) + Number::Float(0.5), Number::Float(_)));
+        assert_eq!(integer(-7) % integer(3), integer(-1));
+    }
+
+    #[test]
+    fn exact_powers() {
+        assert_eq!(integer(2).pow(&integer(10)), Some(integer(1024)));
+        assert_eq!(integer(2).pow(&integer(-2)), Some(fraction(1, 4)));
+        assert_eq!(fraction(2, 3).pow(&integer(2)), Some(fraction(4, 9)));
+        assert!(matches!(
+            integer(2).pow(&integer(-2)),
+            Some(Number::Rational(_))
+        ));
+
+        // 0, 1, and -1 don't grow, so any power of them is fine
+        let huge = Number::Integer(BigInt::from(10).pow(30));
+        assert_eq!(integer(1).pow(&huge), Some(integer(1)));
+        assert_eq!(
+            integer(-1).pow(&(huge.clone() + integer(1))),
+            Some(integer(-1))
+        );
+        assert_eq!(integer(0).pow(&huge), Some(integer(0)));
+    }
+
+    #[test]
+    fn powers_too_large_to_calculate() {
+        assert!(integer(2).pow(&integer(500_000)).is_some());
+        assert_eq!(integer(2).pow(&integer(1_000_001)), None);
+        assert_eq!(integer(3).pow(&integer(1_000_000)), None);
+        assert_eq!(fraction(1, 3).pow(&integer(1_000_000)), None);
+        assert_eq!(
+            integer(2).pow(&Number::Integer(BigInt::from(10).pow(30))),
+            None
+        );
+    }
+
+    #[test]
+    fn inexact_powers() {
+        assert_eq!(integer(4).pow(&exact("0.5")), Some(integer(2)));
+        assert!(matches!(
+            integer(4).pow(&exact("0.5")),
+            Some(Number::Float(_))
+        ));
+        assert!(matches!(
+            integer(-4).pow(&exact("0.5")),
+            Some(Number::Complex(_))
+        ));
+    }
+
+    #[test]
+    fn modpow() {
+        assert_eq!(
+            integer(3).modpow(&integer(200), &integer(7)),
+            Some(integer(2))
+        );
+        assert_eq!(integer(3).modpow(&integer(2), &integer(0)), None);
+        assert_eq!(integer(3).modpow(&integer(-1), &integer(7)), None);
+        assert_eq!(exact("3.5").modpow(&integer(2), &integer(7)), None);
+    }
+
+    #[test]
+    fn factorial() {
+        assert_eq!(integer(5).factorial(), Some(integer(120)));
+        assert_eq!(integer(0).factorial(), Some(integer(1)));
+        assert_eq!(integer(-1).factorial(), None);
+        assert_eq!(exact("2.5").factorial(), None);
+        assert_eq!(integer(MAX_FACTORIAL as i64 + 1).factorial(), None);
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(fraction(1, 8).format(None), "0.125");
+        assert_eq!(fraction(1, 3).format(Some(4)), "0.3333…");
+        assert_eq!(fraction(2, 3).format(Some(4)), "0.6667…");
+        assert_eq!(fraction(-1, 8).format(None), "-0.125");
+        assert_eq!(fraction(1, 8).format(Some(2)), "0.13…");
+        assert_eq!(Number::Float(1e25).to_string(), "1e25");
+        assert_eq!(Number::Float(0.5).to_string(), "0.5");
+        assert_eq!(
+            fraction(7, 4).format_fraction(FractionStyle::Both),
+            Some("7/4 = 1 3/4".to_owned())
+        );
+        assert_eq!(integer(2).format_fraction(FractionStyle::Both), None);
+    }
+
+    #[test]
+    fn formatting_huge_denominators() {
+        let tiny = fraction(1, 10).pow(&integer(10_000)).unwrap();
+        assert_eq!(tiny.format(Some(3)), "0…");
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(fraction(5, 2).round(0), integer(3));
+        assert_eq!(exact("1.2345").round(2), exact("1.23"));
+        assert_eq!(integer(1234).round(-2), integer(1200));
+        assert_eq!(exact("1.5").round(i32::MAX), exact("1.5"));
+    }
+}
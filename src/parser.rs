@@ -4,11 +4,7 @@ use crossterm::{
     ExecutableCommand, QueueableCommand,
 };
 use custom_io::mark_special;
-use evalexpr::eval;
 use inline_colorization::*;
-use itertools::Itertools;
-use regex::Regex;
-use regex_split::RegexSplit;
 use std::{
     collections::VecDeque,
     io::{self, Write},
@@ -17,16 +13,25 @@ use std::{
 };
 
 use crate::{
-    invalid_expression::InvalidExpression, parse_error::ParseError, variable::Variable, *,
+    calc_error::CalcError,
+    evaluator::Evaluator,
+    fraction_style::FractionStyle,
+    function::Function,
+    history_entry::HistoryEntry,
+    lexer::{Token, TokenKind},
+    precision::Precision,
+    value::Value,
+    variable::{Variable, VariableKind},
+    *,
 };
 
 /// Parses an expression and conditionally calculates the result of it after:
 ///
 /// - Determining if it's a [command](parse_commands), performing that command's action instead
 /// - Determining if it's a [comment](parse_comments), and marking it as such
-/// - Handling [continuations](parse_continuations)
 /// - Handling [variable declarations](parse_variable_declarations)
-pub fn parse<S: ToString>(expression: S) -> Result<(), InvalidExpression> {
+/// - Otherwise [calculating](calculate) it, resolving line references, continuations, and variables along the way
+pub fn parse<S: ToString>(expression: S) -> Result<(), CalcError> {
     let mut expression = expression.to_string();
     let expression_raw = expression.to_owned();
 
@@ -46,6 +51,8 @@ pub fn parse<S: ToString>(expression: S) -> Result<(), InvalidExpression> {
             return Ok(());
         }
         "" => {
+            workspace::save_on_exit();
+            history::save_on_exit();
             exit(0);
         }
         _ => (),
@@ -53,9 +60,8 @@ pub fn parse<S: ToString>(expression: S) -> Result<(), InvalidExpression> {
 
     _ = expression.pop();
 
-    let result = parse_commands(&expression);
-    if result {
-        return Ok(());
+    if let Some(result) = parse_commands(&expression) {
+        return result;
     }
 
     if expression.starts_with("//") || expression.starts_with('#') {
@@ -63,43 +69,15 @@ pub fn parse<S: ToString>(expression: S) -> Result<(), InvalidExpression> {
     }
 
     if expression.contains('=') {
-        let split: Vec<&str> = expression.split("=").collect();
-        if split.len() > 2 {
-            return Err(InvalidExpression {
-                message: format!("{color_red}Too many equals signs{color_reset}").to_owned(),
-            });
-        }
-
-        let result = parse_variable_declarations(split);
-        match result {
-            Ok(_) => (),
-            Err(err) => println!("{color_red}{err}{color_reset}"),
-        }
-
-        return Ok(());
-    }
-
-    loop {
-        let start = expression.clone();
-        let line_reference_result = parse_line_references(&mut expression);
-        let Ok(point_in_history) = line_reference_result else {
-            return Err(InvalidExpression::new(
-                line_reference_result.unwrap_err().message,
-            ));
-        };
-
-        if !parse_continuations(&mut expression, point_in_history) {
-            return Ok(());
+        if expression.matches('=').count() > 1 {
+            return Err(CalcError::TooManyEquals);
         }
 
-        if start == expression {
-            break;
-        }
+        return parse_variable_declarations(&expression, false);
     }
 
-    parse_variables(&mut expression)?;
-
-    let result = calculate_and_show_result(&expression);
+    let result = calculate(&expression)?;
+    println!("= {color_blue}{}{color_reset}", format_result(&result));
 
     if time {
         println!(
@@ -108,206 +86,137 @@ pub fn parse<S: ToString>(expression: S) -> Result<(), InvalidExpression> {
         )
     }
 
-    if result {
-        let mut history = HISTORY.lock().unwrap();
+    let mut variables = VARIABLES.lock().unwrap();
 
-        history.push_front(expression_raw);
-    }
+    variables.set_result(result.clone());
 
-    Ok(())
-}
+    let mut history = HISTORY.lock().unwrap();
 
-/// Calculates an expression and displays the results
-///
-/// Only ran after all the parsing functions are ran first
-fn calculate_and_show_result(expression: &str) -> bool {
-    let result = eval(expression);
-    match result {
-        Ok(result) => {
-            println!("= {color_blue}{}{color_reset}", &result)
-        }
-        Err(_) => {
-            println!("{color_red}Failed to calculate expression: check spelling and for typos, or for variable that doesn't exist{color_reset}");
-            return false;
-        }
-    }
+    history.push_front(HistoryEntry::new(expression_raw, Some(result)));
 
-    true
+    Ok(())
 }
 
-/// Parses line references and replaces them with that line
+/// Calculates an expression
 ///
-/// Line references are when the user types, for example: `[2]` and refers to line 2 (index 1 in the expression history)
-fn parse_line_references(expression: &mut String) -> Result<usize, ParseError> {
-    let history = HISTORY.lock().unwrap();
-    let operators = OPERATORS.lock().unwrap();
+/// The expression is [tokenized](lexer::tokenize), [built into a syntax tree](ast::build), then [evaluated](Evaluator)
+pub fn calculate(expression: &str) -> Result<Value, CalcError> {
+    let tokens = lexer::tokenize(expression)?;
+    let tree = ast::build(&tokens)?;
 
-    let mut history_reverse: Vec<String> = history.clone().into();
-    history_reverse.reverse();
-
-    let mut point_in_history: usize = 0;
-    let mut set = false;
-
-    for i in 0..=history.len() {
-        if !set && !expression.starts_with("[") {
-            set = true;
-        }
-
-        let re = Regex::new(&format!("\\[{}\\]", i)).unwrap();
-
-        if re.is_match(expression) {
-            if i == 0 {
-                return Err(ParseError::new("0 is an invalid line index"));
-            }
+    let variables = VARIABLES.lock().unwrap();
+    let history = HISTORY.lock().unwrap();
 
-            *expression = re
-                .replace_all(expression, format!("({})", history_reverse[i - 1].trim()))
-                .to_string();
-        }
+    Evaluator::new(&variables, &history).evaluate(&tree)
+}
 
-        // *expression = expression.replace(&format!("[{}]", i), &history_tmp[i - 1]);
+/// Calculates what `expression` would show if it were entered, without running commands, declaring variables, or adding to the history
+///
+/// A variable declaration previews the value it would be given, or the error if its name can't be declared.
+/// Returns [None] for anything without a result to preview, like commands, comments, and function declarations
+pub fn preview(expression: &str) -> Option<Result<Value, CalcError>> {
+    if find_command(expression).is_some()
+        || expression.starts_with("//")
+        || expression.starts_with('#')
+    {
+        return None;
+    }
 
-        for operator in operators.iter() {
-            if set {
-                break;
-            }
+    let Some((name, value)) = expression.split_once('=') else {
+        return (!expression.trim().is_empty()).then(|| calculate(expression));
+    };
 
-            if expression.starts_with(&format!("({}", operator)) {
-                point_in_history = i;
-                set = true;
-            }
-        }
+    if value.contains('=') {
+        return None;
     }
 
-    let re_over_history_len = Regex::new(r"\[(?<num>.*)]").unwrap();
-
-    if let Some(captures) = re_over_history_len.captures(expression) {
-        let index = captures.name("num").unwrap().as_str();
+    // Checked the same way as when it's entered, so a read-only name shows its error before the value does
+    let name_offset = expression.len() - expression.trim_start().len();
+    match declared_function(name.trim(), name_offset, false) {
+        Err(err) => return Some(Err(err)),
+        Ok(Some(_)) => return None,
+        Ok(None) => (),
+    }
 
-        // Index NaN
-        let Ok(index_num) = index.parse::<isize>() else {
-            return Err(ParseError::new("Index given for line reference is invalid"));
-        };
+    let value_offset = expression.len() - value.trim_start().len();
+    let value = value.trim();
+    (!value.is_empty()).then(|| calculate(value).map_err(|err| err.shifted(value_offset)))
+}
 
-        // Index negative
-        if index_num < 0 {
-            return Err(ParseError::new(
-                "Negative numbers are not valid indices for line references",
-            ));
-        }
+/// Formats a result as a decimal, followed by its fraction if it's an exact fraction and fractions are shown, or its polar form if it's complex
+///
+/// Results with units are only shown as a decimal
+pub fn format_result(result: &Value) -> String {
+    let (_, digits) = Precision::current();
+    let decimal = result.format(digits);
+    if result.has_unit() {
+        return decimal;
+    }
 
-        if index_num as usize >= history.len() {
-            return Err(ParseError::new(
-                "Index given is outside of history's bounds",
-            ));
-        }
+    let number = &result.number;
+    let alternative = number
+        .format_fraction(FractionStyle::current())
+        .or_else(|| number.format_polar());
 
-        // Valid line reference still exists so drop mutexes and recurse
-        drop(history);
-        drop(operators);
-        return parse_line_references(expression);
+    match alternative {
+        Some(alternative) => format!("{decimal} = {alternative}"),
+        None => decimal,
     }
+}
 
-    Ok(point_in_history)
+/// The command `expression` runs, if its first word is the name or alias of one
+///
+/// Used both to run commands and to tell them apart from expressions while typing, so the two always agree
+pub fn find_command(expression: &str) -> Option<Command> {
+    let word = expression.split(' ').next().unwrap_or_default();
+    let commands = COMMANDS.lock().unwrap();
+
+    commands
+        .values()
+        .find(|command| {
+            command.name == word
+                || command
+                    .aliases
+                    .as_ref()
+                    .is_some_and(|aliases| aliases.contains(&word))
+        })
+        .cloned()
 }
 
 /// Parses commands
 ///
+/// Returns the result of the command's action, or [None] if the expression isn't a command
+///
 /// Built-in commands can be found in [default_commands]
-fn parse_commands(expression: &str) -> bool {
-    let commands_lock = COMMANDS.lock().unwrap();
-    let commands = commands_lock.clone();
-    drop(commands_lock);
+fn parse_commands(expression: &str) -> Option<Result<(), CalcError>> {
+    let command = find_command(expression)?;
+    mark_special("/", expression);
 
     let mut split: VecDeque<&str> = expression.split(" ").collect();
-    if !split.is_empty() {
-        for (name, command) in commands.iter() {
-            if split[0] == name
-                || command.aliases.is_some()
-                    && command.aliases.as_ref().unwrap().contains(&split[0])
-            {
-                mark_special("/", expression);
-                _ = split.pop_front();
-
-                let parameters = split.clone().into_iter().collect::<Vec<&str>>().join(" ");
-
-                let mut history = HISTORY.lock().unwrap();
-                history.push_back(format!("{} {}", name, &parameters));
-                drop(history);
-
-                let parameters = if parameters.is_empty() {
-                    None
-                } else {
-                    Some(parameters.as_str())
-                };
-
-                (command.action)(parameters);
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
-/// Parses continuations
-///
-/// Continuations are expressions that rely on the result of the previous calculation, and use it as the first operand
-fn parse_continuations(expression: &mut String, mut point_in_history: usize) -> bool {
-    let expression_clone = expression.to_owned();
-    let mut expression_trimmed = expression_clone.trim().to_owned();
-
-    let operators = OPERATORS.lock().unwrap();
-
-    let mut opening_parens_to_restore = 0;
-
-    loop {
-        let mut encountered_operator = false;
-        if point_in_history > 0 || expression_trimmed.starts_with("(") {
-            let mut expr_chars = expression_trimmed.chars();
-            expr_chars.next();
-            expression_trimmed = expr_chars.as_str().to_owned();
-            opening_parens_to_restore += 1;
-        }
+    _ = split.pop_front();
 
-        for operator in operators.iter() {
-            if expression_trimmed.starts_with(operator) {
-                encountered_operator = true;
-
-                let history = HISTORY.lock().unwrap();
-                let expr = history.get(point_in_history);
-                point_in_history += 1;
-
-                if expr.is_none() {
-                    println!(
-                        "{color_red}No expressions in history to use for continuation{color_reset}"
-                    );
-                    return false;
-                } else {
-                    let mut expr = expr.unwrap().to_owned();
-                    expr = expr.strip_suffix('\n').unwrap_or(&expr).to_owned();
-                    expression_trimmed = format!("({}){expression_trimmed}", expr);
-
-                    break;
-                }
-            }
-        }
+    let parameters = split.into_iter().collect::<Vec<&str>>().join(" ");
 
-        if !encountered_operator {
-            break;
-        }
-    }
+    let mut history = HISTORY.lock().unwrap();
+    history.push_front(HistoryEntry::new(
+        format!("{} {}", command.name, &parameters),
+        None,
+    ));
+    drop(history);
 
-    *expression = format!(
-        "{}{expression_trimmed}",
-        "(".repeat(opening_parens_to_restore)
-    );
+    // Errors point into the parameters, which come at the end of the expression
+    let offset = expression.len() - parameters.len();
+    let parameters = if parameters.is_empty() {
+        None
+    } else {
+        Some(parameters.as_str())
+    };
 
-    true
+    Some((command.action)(parameters).map_err(|err| err.shifted(offset)))
 }
 
 /// Parses comments, starting with both `//` and `#`
-fn parse_comments(expression: String) -> Result<(), InvalidExpression> {
+fn parse_comments(expression: String) -> Result<(), CalcError> {
     let comment_prefix = if expression.starts_with('/') {
         "//"
     } else {
@@ -343,113 +252,155 @@ fn parse_comments(expression: String) -> Result<(), InvalidExpression> {
     Ok(())
 }
 
-fn parse_variables(expression: &mut String) -> Result<(), InvalidExpression> {
-    let variables = VARIABLES.lock().unwrap();
-
-    let mut variables_in_expression = false;
-
-    for key in variables.variables.clone().keys().sorted().rev() {
-        if expression.contains(key) {
-            variables_in_expression = true;
-            break;
-        } else if let Some(aliases) = &variables.variables[key].aliases {
-            for alias in aliases.iter() {
-                if expression.contains(alias) {
-                    variables_in_expression = true;
-                    break;
-                }
-            }
-        }
+/// Parses variable declarations like `r = 2 * pi`, and function declarations like `f(x, y) = x^2 + y`
+///
+/// The right-hand side of a variable declaration is [calculated](calculate) like any other expression, and the result is stored in [VARIABLES]
+///
+/// Built-in constants and `ans` can only be replaced if `shadow` is set, which is what the `shadow` command does
+pub fn parse_variable_declarations(expression: &str, shadow: bool) -> Result<(), CalcError> {
+    let (name, value_str) = expression.split_once('=').unwrap();
+
+    let name_offset = expression.len() - expression.trim_start().len();
+    let name = name.trim();
+
+    // Spans from the right-hand side need to be shifted so they line up with the whole expression
+    let value_offset = expression.len() - value_str.trim_start().len();
+    let value_str = value_str.trim();
+
+    let function = declared_function(name, name_offset, shadow)?;
+
+    if let Some((function_name, parameters)) = function {
+        return parse_function_declaration(
+            &function_name,
+            parameters,
+            value_str,
+            value_offset,
+            shadow,
+        );
     }
 
-    if !variables_in_expression {
-        return Ok(());
-    }
+    let value = calculate(value_str).map_err(|err| err.shifted(value_offset))?;
 
-    let operators = OPERATORS.lock().unwrap();
-
-    let mut regex_str = operators
-        .iter()
-        .map(|o| format!("\\{o}|"))
-        .collect::<Vec<String>>()
-        .join("");
-    regex_str.pop();
-
-    let re = Regex::new(&regex_str).unwrap();
-    let result: Vec<&str> = re.split_inclusive(expression).collect();
-    let mut result2: Vec<String> = result
-        .clone()
-        .into_iter()
-        .flat_map(|f| re.split(f))
-        .map(|f| f.trim().to_string())
-        .collect();
-
-    let mut j = 0usize;
-
-    for (i, str) in result.iter().enumerate() {
-        if i == result.len() - 1 {
-            break;
-        }
+    let formatted_value = format_result(&value);
 
-        for operator in operators.iter() {
-            if str.ends_with(operator) {
-                result2[i + j + 1] = operator.clone();
-                j += 1;
-                break;
-            }
-        }
+    let variable = Variable::new(name, value, None, VariableKind::User);
+
+    let mut variables = VARIABLES.lock().unwrap();
+
+    variables.add(variable);
+
+    // The shadow command already marked the line
+    if !shadow {
+        mark_special("=", &format!("{name} = {value_str}"));
     }
 
-    let re2 = Regex::new("^(?<leftparen>\\(*)(?<value>\\D+)(?<rightparen>\\)*)").unwrap();
+    println!("{color_blue}{name} = {formatted_value}{color_reset}");
+
+    Ok(())
+}
+
+/// The function the left-hand side of a declaration declares, with its parameters, or [None] if it declares a variable
+///
+/// Errors if `name` isn't a valid name, or is read-only and the declaration doesn't shadow it
+fn declared_function(
+    name: &str,
+    name_offset: usize,
+    shadow: bool,
+) -> Result<Option<(String, Vec<String>)>, CalcError> {
+    let invalid_name = || CalcError::InvalidVariableName {
+        name: name.to_owned(),
+        span: name_offset..name_offset + name.len(),
+    };
 
-    for (i, str) in result2.iter_mut().enumerate() {
-        // Skip operators and numbers
-        if i % 2 != 0 || str.parse::<f64>().is_ok() {
-            continue;
+    let tokens = lexer::tokenize(name).unwrap_or_default();
+    let function = match tokens.as_slice() {
+        [Token {
+            kind: TokenKind::Identifier(_),
+            ..
+        }] => None,
+        [Token {
+            kind: TokenKind::Identifier(function_name),
+            ..
+        }, Token {
+            kind: TokenKind::LeftParen,
+            ..
+        }, parameters @ .., Token {
+            kind: TokenKind::RightParen,
+            ..
+        }] => {
+            let parameters = parse_parameters(parameters).ok_or_else(invalid_name)?;
+            Some((function_name.clone(), parameters))
         }
+        _ => return Err(invalid_name()),
+    };
 
-        let str_copy = str.to_owned();
-        // str_copy = str_copy.replace("(", "");
-        // str_copy = str_copy.replace(")", "");
+    let declared_name = function
+        .as_ref()
+        .map_or(name, |(function_name, _)| function_name);
+    let mut kind = VARIABLES.lock().unwrap().kind(declared_name);
 
-        let variable = variables.get(str_copy);
-        if let Some(variable) = variable {
-            let value = variable.value;
-            let string = value.to_string();
+    // A variable doesn't get in the way of calling a built-in function with the same name, but a function would replace it
+    if function.is_some() && BuiltinFunction::find(declared_name).is_some() {
+        kind = Some(VariableKind::Builtin);
+    }
 
-            *str = re2
-                .replace_all(str, format!("${{leftparen}}{string}${{rightparen}}"))
-                .to_string();
+    match kind {
+        Some(kind) if !shadow && !kind.is_assignable() => {
+            return Err(CalcError::ReadOnlyVariable {
+                name: declared_name.to_owned(),
+                kind,
+                span: name_offset..name_offset + declared_name.len(),
+            });
         }
+        _ => (),
     }
 
-    *expression = result2.join("");
-
-    Ok(())
+    Ok(function)
 }
 
-fn parse_variable_declarations(expression: Vec<&str>) -> Result<(), InvalidExpression> {
-    let name = expression[0].trim();
-    let value_str = expression[1].trim();
-    let value = value_str.parse::<f64>();
+/// Reads a comma-separated parameter list, returning [None] if it's malformed or has duplicate names
+fn parse_parameters(tokens: &[Token]) -> Option<Vec<String>> {
+    let mut parameters: Vec<String> = Vec::new();
 
-    if value.is_err() {
-        return Err(InvalidExpression {
-            message: format!("Value given for variable {name} (\"{value_str}\") is not a number"),
-        });
+    for (i, token) in tokens.iter().enumerate() {
+        match &token.kind {
+            TokenKind::Identifier(parameter) if i % 2 == 0 && !parameters.contains(parameter) => {
+                parameters.push(parameter.clone())
+            }
+            TokenKind::Comma if i % 2 == 1 && i != tokens.len() - 1 => (),
+            _ => return None,
+        }
     }
 
-    let value = value.unwrap();
+    Some(parameters)
+}
 
-    let variable = Variable::new_f64(name, value, None);
+/// Stores a user-defined function
+///
+/// The body is only checked for syntax errors here, names in it are looked up when the function is called
+fn parse_function_declaration(
+    name: &str,
+    parameters: Vec<String>,
+    body: &str,
+    body_offset: usize,
+    shadow: bool,
+) -> Result<(), CalcError> {
+    let tokens = lexer::tokenize(body).map_err(|err| err.shifted(body_offset))?;
+    let tree = ast::build(&tokens).map_err(|err| err.shifted(body_offset))?;
+
+    let function = Function::new(name, parameters, tree, body);
+    let signature = function.signature();
 
     let mut variables = VARIABLES.lock().unwrap();
 
-    variables.add(variable);
+    variables.add_function(function);
 
-    mark_special("=", &format!("{name} = {value_str}"));
+    // The shadow command already marked the line
+    if !shadow {
+        mark_special("=", &format!("{signature} = {body}"));
+    }
 
-    println!("{color_blue}{name} = {value}{color_reset}");
+    println!("{color_blue}{signature} = {body}{color_reset}");
 
     Ok(())
 }
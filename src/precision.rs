@@ -0,0 +1,39 @@
+use std::fmt::Display;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::CONFIG;
+
+/// Number backend used for calculations
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Precision {
+    /// Decimals are 64-bit floating point numbers, fast but `0.1 + 0.2` isn't exactly `0.3`
+    #[default]
+    #[serde(rename = "float")]
+    Float,
+    /// Decimals are arbitrary-precision fractions, so they stay exact through `+ - * /`
+    #[serde(rename = "exact")]
+    Exact,
+}
+
+impl Display for Precision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Precision::Float => "float",
+            Precision::Exact => "exact",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl Precision {
+    /// Precision and number of digits to show exact results with, as currently set in [CONFIG]
+    pub fn current() -> (Self, Option<usize>) {
+        let config = CONFIG.lock().unwrap();
+        config
+            .as_ref()
+            .map(|config| (config.precision, config.digits))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,781 @@
+//! Physical units, SI prefixes, and the dimensions they measure
+use std::{
+    fmt::Display,
+    ops::{Div, Mul},
+};
+
+use num_rational::BigRational;
+use num_traits::Zero;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::number::Number;
+
+/// Number of base dimensions: the 7 SI ones (length, mass, time, current, temperature, amount of substance, and luminous intensity),
+/// plus information so data sizes can be converted
+const BASE_DIMENSIONS: usize = 8;
+
+/// Symbols of the base units, in the same order as a [Dimension]'s exponents
+const BASE_SYMBOLS: [&str; BASE_DIMENSIONS] = ["m", "kg", "s", "A", "K", "mol", "cd", "B"];
+
+/// What a quantity measures, as the exponents of each SI base unit
+///
+/// Acceleration (m/s^2) is `[1, 0, -2, 0, 0, 0, 0, 0]`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Dimension(pub [i8; BASE_DIMENSIONS]);
+
+impl Dimension {
+    /// Dimension of plain numbers
+    pub const NONE: Dimension = Dimension([0; BASE_DIMENSIONS]);
+
+    const LENGTH: Dimension = Dimension([1, 0, 0, 0, 0, 0, 0, 0]);
+    const MASS: Dimension = Dimension([0, 1, 0, 0, 0, 0, 0, 0]);
+    const TIME: Dimension = Dimension([0, 0, 1, 0, 0, 0, 0, 0]);
+    const CURRENT: Dimension = Dimension([0, 0, 0, 1, 0, 0, 0, 0]);
+    const TEMPERATURE: Dimension = Dimension([0, 0, 0, 0, 1, 0, 0, 0]);
+    const AMOUNT: Dimension = Dimension([0, 0, 0, 0, 0, 1, 0, 0]);
+    const LUMINOUS_INTENSITY: Dimension = Dimension([0, 0, 0, 0, 0, 0, 1, 0]);
+    const VOLUME: Dimension = Dimension([3, 0, 0, 0, 0, 0, 0, 0]);
+    const FREQUENCY: Dimension = Dimension([0, 0, -1, 0, 0, 0, 0, 0]);
+    const FORCE: Dimension = Dimension([1, 1, -2, 0, 0, 0, 0, 0]);
+    const PRESSURE: Dimension = Dimension([-1, 1, -2, 0, 0, 0, 0, 0]);
+    const ENERGY: Dimension = Dimension([2, 1, -2, 0, 0, 0, 0, 0]);
+    const POWER: Dimension = Dimension([2, 1, -3, 0, 0, 0, 0, 0]);
+    const CHARGE: Dimension = Dimension([0, 0, 1, 1, 0, 0, 0, 0]);
+    const VOLTAGE: Dimension = Dimension([2, 1, -3, -1, 0, 0, 0, 0]);
+    const RESISTANCE: Dimension = Dimension([2, 1, -3, -2, 0, 0, 0, 0]);
+    const CONDUCTANCE: Dimension = Dimension([-2, -1, 3, 2, 0, 0, 0, 0]);
+    const CAPACITANCE: Dimension = Dimension([-2, -1, 4, 2, 0, 0, 0, 0]);
+    const MAGNETIC_FLUX: Dimension = Dimension([2, 1, -2, -1, 0, 0, 0, 0]);
+    const MAGNETIC_FLUX_DENSITY: Dimension = Dimension([0, 1, -2, -1, 0, 0, 0, 0]);
+    const INDUCTANCE: Dimension = Dimension([2, 1, -2, -2, 0, 0, 0, 0]);
+    const AREA: Dimension = Dimension([2, 0, 0, 0, 0, 0, 0, 0]);
+    const SPEED: Dimension = Dimension([1, 0, -1, 0, 0, 0, 0, 0]);
+    const INFORMATION: Dimension = Dimension([0, 0, 0, 0, 0, 0, 0, 1]);
+
+    pub fn is_none(&self) -> bool {
+        *self == Dimension::NONE
+    }
+
+    /// Raises every exponent to `power`, or [None] if an exponent would overflow
+    pub fn pow(&self, power: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for exponent in &mut exponents {
+            *exponent = exponent.checked_mul(power)?;
+        }
+
+        Some(Dimension(exponents))
+    }
+
+    /// Takes the `n`th root of the dimension, or [None] if an exponent isn't divisible by `n`, like the square root of m^3
+    pub fn root(&self, n: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for exponent in &mut exponents {
+            if *exponent % n != 0 {
+                return None;
+            }
+
+            *exponent /= n;
+        }
+
+        Some(Dimension(exponents))
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension;
+
+    fn mul(self, rhs: Dimension) -> Dimension {
+        let mut exponents = self.0;
+        for (exponent, other) in exponents.iter_mut().zip(rhs.0) {
+            *exponent = exponent.saturating_add(other);
+        }
+
+        Dimension(exponents)
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension;
+
+    fn div(self, rhs: Dimension) -> Dimension {
+        let mut exponents = self.0;
+        for (exponent, other) in exponents.iter_mut().zip(rhs.0) {
+            *exponent = exponent.saturating_sub(other);
+        }
+
+        Dimension(exponents)
+    }
+}
+
+impl Display for Dimension {
+    /// Shows the simplest unit for the dimension, like `N` for kg*m/s^2, or a product of base units if there isn't a named one
+    ///
+    /// The result can be typed back in after a number, like `3 kg*m^2/s` or `3 m^-1`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(unit) = UNITS
+            .iter()
+            .find(|unit| unit.dimension == *self && unit.factor == "1")
+        {
+            return write!(f, "{}", unit.symbol);
+        }
+
+        let power = |symbol: &str, exponent: i8| match exponent {
+            1 => symbol.to_owned(),
+            _ => format!("{symbol}^{exponent}"),
+        };
+
+        let numerator: Vec<String> = BASE_SYMBOLS
+            .iter()
+            .zip(self.0)
+            .filter(|(_, exponent)| *exponent > 0)
+            .map(|(symbol, exponent)| power(symbol, exponent))
+            .collect();
+
+        // Without anything to divide, negative exponents are written as they are
+        if numerator.is_empty() {
+            let units: Vec<String> = BASE_SYMBOLS
+                .iter()
+                .zip(self.0)
+                .filter(|(_, exponent)| *exponent != 0)
+                .map(|(symbol, exponent)| power(symbol, exponent))
+                .collect();
+
+            return write!(f, "{}", units.join("*"));
+        }
+
+        write!(f, "{}", numerator.join("*"))?;
+
+        for (symbol, exponent) in BASE_SYMBOLS.iter().zip(self.0) {
+            if exponent < 0 {
+                write!(f, "/{}", power(symbol, -exponent))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Which prefixes can be put in front of a unit's symbol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prefixes {
+    None,
+    /// SI prefixes like the `k` in `km`
+    Si,
+    /// SI prefixes and binary ones like the `Ki` in `KiB`, for data sizes
+    Binary,
+}
+
+/// A unit that can be written after a number, like the `N` in `5 N`
+pub struct Unit {
+    pub symbol: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Size of the unit in SI base units, written as a decimal or a fraction of decimals so it stays exact
+    pub factor: &'static str,
+    /// Value of zero in this unit, counted in this unit from the SI zero, for temperatures like °C
+    pub offset: &'static str,
+    pub dimension: Dimension,
+    pub prefixes: Prefixes,
+}
+
+/// Every unit SI prefixes can be combined with, and a few common non-SI ones
+///
+/// A named unit with a factor of 1 is used to show results of its dimension, so the first one listed for a dimension wins
+pub const UNITS: &[Unit] = &[
+    Unit {
+        symbol: "m",
+        aliases: &["metre", "metres", "meter", "meters"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::LENGTH,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "g",
+        aliases: &["gram", "grams"],
+        factor: "0.001",
+        offset: "0",
+        dimension: Dimension::MASS,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "s",
+        aliases: &["sec", "second", "seconds"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::TIME,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "A",
+        aliases: &["amp", "amps", "ampere", "amperes"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::CURRENT,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "K",
+        aliases: &["kelvin"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::TEMPERATURE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "mol",
+        aliases: &["mole", "moles"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::AMOUNT,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "cd",
+        aliases: &["candela"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::LUMINOUS_INTENSITY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "Hz",
+        aliases: &["hertz"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::FREQUENCY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "N",
+        aliases: &["newton", "newtons"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::FORCE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "Pa",
+        aliases: &["pascal", "pascals"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::PRESSURE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "J",
+        aliases: &["joule", "joules"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::ENERGY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "W",
+        aliases: &["watt", "watts"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::POWER,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "C",
+        aliases: &["coulomb", "coulombs"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::CHARGE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "V",
+        aliases: &["volt", "volts"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::VOLTAGE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "Ω",
+        aliases: &["ohm", "ohms"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::RESISTANCE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "S",
+        aliases: &["siemens"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::CONDUCTANCE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "F",
+        aliases: &["farad", "farads"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::CAPACITANCE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "Wb",
+        aliases: &["weber", "webers"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::MAGNETIC_FLUX,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "T",
+        aliases: &["tesla", "teslas"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::MAGNETIC_FLUX_DENSITY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "H",
+        aliases: &["henry", "henries"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::INDUCTANCE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "L",
+        aliases: &["l", "litre", "litres", "liter", "liters"],
+        factor: "0.001",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "t",
+        aliases: &["tonne", "tonnes"],
+        factor: "1000",
+        offset: "0",
+        dimension: Dimension::MASS,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "B",
+        aliases: &["byte", "bytes"],
+        factor: "1",
+        offset: "0",
+        dimension: Dimension::INFORMATION,
+        prefixes: Prefixes::Binary,
+    },
+    Unit {
+        symbol: "bit",
+        aliases: &["bits"],
+        factor: "0.125",
+        offset: "0",
+        dimension: Dimension::INFORMATION,
+        prefixes: Prefixes::Binary,
+    },
+    // Commonly written with a capital K, though SI's kilo is a lowercase k
+    Unit {
+        symbol: "KB",
+        aliases: &[],
+        factor: "1000",
+        offset: "0",
+        dimension: Dimension::INFORMATION,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "°C",
+        aliases: &["degC", "celsius"],
+        factor: "1",
+        offset: "273.15",
+        dimension: Dimension::TEMPERATURE,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "°F",
+        aliases: &["degF", "fahrenheit"],
+        factor: "5/9",
+        offset: "459.67",
+        dimension: Dimension::TEMPERATURE,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "in",
+        aliases: &["inch", "inches"],
+        factor: "0.0254",
+        offset: "0",
+        dimension: Dimension::LENGTH,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "ft",
+        aliases: &["foot", "feet"],
+        factor: "0.3048",
+        offset: "0",
+        dimension: Dimension::LENGTH,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "yd",
+        aliases: &["yard", "yards"],
+        factor: "0.9144",
+        offset: "0",
+        dimension: Dimension::LENGTH,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "mi",
+        aliases: &["mile", "miles"],
+        factor: "1609.344",
+        offset: "0",
+        dimension: Dimension::LENGTH,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "nmi",
+        aliases: &["nauticalmile", "nauticalmiles"],
+        factor: "1852",
+        offset: "0",
+        dimension: Dimension::LENGTH,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "lb",
+        aliases: &["lbs", "pound", "pounds"],
+        factor: "0.45359237",
+        offset: "0",
+        dimension: Dimension::MASS,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "oz",
+        aliases: &["ounce", "ounces"],
+        factor: "0.028349523125",
+        offset: "0",
+        dimension: Dimension::MASS,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "st",
+        aliases: &["stone", "stones"],
+        factor: "6.35029318",
+        offset: "0",
+        dimension: Dimension::MASS,
+        prefixes: Prefixes::None,
+    },
+    // US customary volumes
+    Unit {
+        symbol: "gal",
+        aliases: &["gallon", "gallons"],
+        factor: "0.003785411784",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "qt",
+        aliases: &["quart", "quarts"],
+        factor: "0.000946352946",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "pt",
+        aliases: &["pint", "pints"],
+        factor: "0.000473176473",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "cup",
+        aliases: &["cups"],
+        factor: "0.0002365882365",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "floz",
+        aliases: &[],
+        factor: "0.0000295735295625",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "tbsp",
+        aliases: &["tablespoon", "tablespoons"],
+        factor: "0.00001478676478125",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "tsp",
+        aliases: &["teaspoon", "teaspoons"],
+        factor: "0.00000492892159375",
+        offset: "0",
+        dimension: Dimension::VOLUME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "ha",
+        aliases: &["hectare", "hectares"],
+        factor: "10000",
+        offset: "0",
+        dimension: Dimension::AREA,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "acre",
+        aliases: &["acres"],
+        factor: "4046.8564224",
+        offset: "0",
+        dimension: Dimension::AREA,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "mph",
+        aliases: &[],
+        factor: "0.44704",
+        offset: "0",
+        dimension: Dimension::SPEED,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "kph",
+        aliases: &[],
+        factor: "1000/3600",
+        offset: "0",
+        dimension: Dimension::SPEED,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "kn",
+        aliases: &["knot", "knots"],
+        factor: "1852/3600",
+        offset: "0",
+        dimension: Dimension::SPEED,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "bar",
+        aliases: &["bars"],
+        factor: "100000",
+        offset: "0",
+        dimension: Dimension::PRESSURE,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "atm",
+        aliases: &["atmosphere", "atmospheres"],
+        factor: "101325",
+        offset: "0",
+        dimension: Dimension::PRESSURE,
+        prefixes: Prefixes::None,
+    },
+    // Pound-force per square inch
+    Unit {
+        symbol: "psi",
+        aliases: &[],
+        factor: "4.4482216152605/0.00064516",
+        offset: "0",
+        dimension: Dimension::PRESSURE,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "Torr",
+        aliases: &["torr"],
+        factor: "101325/760",
+        offset: "0",
+        dimension: Dimension::PRESSURE,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "mmHg",
+        aliases: &[],
+        factor: "133.322387415",
+        offset: "0",
+        dimension: Dimension::PRESSURE,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "cal",
+        aliases: &["calorie", "calories"],
+        factor: "4.184",
+        offset: "0",
+        dimension: Dimension::ENERGY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "Wh",
+        aliases: &[],
+        factor: "3600",
+        offset: "0",
+        dimension: Dimension::ENERGY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "eV",
+        aliases: &["electronvolt", "electronvolts"],
+        factor: "1.602176634e-19",
+        offset: "0",
+        dimension: Dimension::ENERGY,
+        prefixes: Prefixes::Si,
+    },
+    Unit {
+        symbol: "BTU",
+        aliases: &["btu"],
+        factor: "1055.05585262",
+        offset: "0",
+        dimension: Dimension::ENERGY,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "min",
+        aliases: &["minute", "minutes"],
+        factor: "60",
+        offset: "0",
+        dimension: Dimension::TIME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "h",
+        aliases: &["hr", "hour", "hours"],
+        factor: "3600",
+        offset: "0",
+        dimension: Dimension::TIME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "d",
+        aliases: &["day", "days"],
+        factor: "86400",
+        offset: "0",
+        dimension: Dimension::TIME,
+        prefixes: Prefixes::None,
+    },
+    Unit {
+        symbol: "wk",
+        aliases: &["week", "weeks"],
+        factor: "604800",
+        offset: "0",
+        dimension: Dimension::TIME,
+        prefixes: Prefixes::None,
+    },
+    // Julian year, 365.25 days
+    Unit {
+        symbol: "yr",
+        aliases: &["year", "years"],
+        factor: "31557600",
+        offset: "0",
+        dimension: Dimension::TIME,
+        prefixes: Prefixes::None,
+    },
+];
+
+/// Binary prefixes and the power of two they stand for
+const BINARY_PREFIXES: &[(&str, i32)] = &[
+    ("Ki", 10),
+    ("Mi", 20),
+    ("Gi", 30),
+    ("Ti", 40),
+    ("Pi", 50),
+    ("Ei", 60),
+    ("Zi", 70),
+    ("Yi", 80),
+];
+
+/// SI prefixes and the power of ten they stand for
+const PREFIXES: &[(&str, i32)] = &[
+    ("Q", 30),
+    ("R", 27),
+    ("Y", 24),
+    ("Z", 21),
+    ("E", 18),
+    ("P", 15),
+    ("T", 12),
+    ("G", 9),
+    ("M", 6),
+    ("k", 3),
+    ("h", 2),
+    ("da", 1),
+    ("d", -1),
+    ("c", -2),
+    ("m", -3),
+    ("µ", -6),
+    ("μ", -6),
+    ("u", -6),
+    ("n", -9),
+    ("p", -12),
+    ("f", -15),
+    ("a", -18),
+    ("z", -21),
+    ("y", -24),
+    ("r", -27),
+    ("q", -30),
+];
+
+/// A unit found by [Unit::find], with any prefix applied
+pub struct FoundUnit {
+    /// Size of the unit in SI base units
+    pub factor: BigRational,
+    /// Value of zero in this unit, in SI base units
+    pub offset: BigRational,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    /// Looks up a unit by its symbol or name, with an optional prefix like the `k` in `km` or the `Ki` in `KiB`
+    pub fn find(name: &str) -> Option<FoundUnit> {
+        // Exact names win over prefixes, so `min` is minutes rather than milli-inches
+        if let Some(unit) = UNITS
+            .iter()
+            .find(|unit| unit.symbol == name || unit.aliases.contains(&name))
+        {
+            return Some(FoundUnit {
+                factor: unit.factor(),
+                offset: unit.offset() * unit.factor(),
+                dimension: unit.dimension,
+            });
+        }
+
+        let prefixed = |prefixes: &[(&str, i32)], base: i64, allowed: &[Prefixes]| {
+            prefixes.iter().find_map(|(prefix, power)| {
+                let unit = UNITS.iter().find(|unit| {
+                    allowed.contains(&unit.prefixes)
+                        && name.strip_prefix(prefix) == Some(unit.symbol)
+                })?;
+
+                let scale = BigRational::from_integer(base.into()).pow(*power);
+                Some(FoundUnit {
+                    factor: unit.factor() * scale,
+                    offset: BigRational::zero(),
+                    dimension: unit.dimension,
+                })
+            })
+        };
+
+        prefixed(PREFIXES, 10, &[Prefixes::Si, Prefixes::Binary])
+            .or_else(|| prefixed(BINARY_PREFIXES, 2, &[Prefixes::Binary]))
+    }
+
+    fn factor(&self) -> BigRational {
+        parse_fraction(self.factor)
+    }
+
+    fn offset(&self) -> BigRational {
+        parse_fraction(self.offset)
+    }
+}
+
+/// Parses a decimal like `0.0254` or a fraction of decimals like `5/9`
+fn parse_fraction(text: &str) -> BigRational {
+    match text.split_once('/') {
+        Some((numerator, denominator)) => {
+            Number::parse_literal(numerator).unwrap() / Number::parse_literal(denominator).unwrap()
+        }
+        None => Number::parse_literal(text).unwrap(),
+    }
+}
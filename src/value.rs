@@ -0,0 +1,91 @@
+//! Numbers with units
+use std::fmt::Display;
+
+use crate::{number::Number, unit::Dimension};
+
+/// Result of a calculation, a [Number] along with the [Dimension] it measures
+#[derive(Clone, Debug)]
+pub struct Value {
+    /// Magnitude in SI base units, so `5 km` is stored as 5000
+    pub number: Number,
+    pub dimension: Dimension,
+    /// Unit the value is shown in, if it isn't shown in the simplest SI unit
+    pub display_unit: Option<DisplayUnit>,
+}
+
+/// A unit a value was written in or converted to, like `km` or `m/s`
+#[derive(Clone, Debug)]
+pub struct DisplayUnit {
+    pub name: String,
+    /// Size of the unit in SI base units
+    pub factor: Number,
+    /// Value of zero in this unit, in SI base units, which is only nonzero for temperatures like °C
+    pub offset: Number,
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        Value::new(number, Dimension::NONE)
+    }
+}
+
+impl Value {
+    pub fn new(number: Number, dimension: Dimension) -> Self {
+        Self {
+            number,
+            dimension,
+            display_unit: None,
+        }
+    }
+
+    pub fn has_unit(&self) -> bool {
+        !self.dimension.is_none()
+    }
+
+    /// The same value, shown in `display_unit` instead
+    pub fn with_display_unit(mut self, display_unit: Option<DisplayUnit>) -> Self {
+        self.display_unit = display_unit;
+        self
+    }
+
+    /// The number as it's shown, in the display unit if there is one
+    pub fn displayed_number(&self) -> Number {
+        match &self.display_unit {
+            Some(unit) => (self.number.clone() - unit.offset.clone()) / unit.factor.clone(),
+            None => self.number.clone(),
+        }
+    }
+
+    fn unit_name(&self) -> String {
+        match &self.display_unit {
+            Some(unit) => unit.name.clone(),
+            None => self.dimension.to_string(),
+        }
+    }
+
+    /// Formats the value for showing a result, see [Number::format]
+    pub fn format(&self, digits: Option<usize>) -> String {
+        let number = self.displayed_number().format(digits);
+        if self.has_unit() {
+            format!("{number} {}", self.unit_name())
+        } else {
+            number
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let number = self.displayed_number();
+        match f.precision() {
+            Some(precision) => write!(f, "{number:.precision$}")?,
+            None => write!(f, "{number}")?,
+        }
+
+        if self.has_unit() {
+            write!(f, " {}", self.unit_name())?;
+        }
+
+        Ok(())
+    }
+}
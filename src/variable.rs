@@ -1,16 +1,68 @@
+use std::fmt::Display;
+
+use crate::{number::Number, value::Value};
+
 #[derive(Clone, Debug)]
 pub struct Variable {
     pub key: Box<str>,
-    pub value: f64,
+    pub value: Value,
     pub aliases: Option<Vec<String>>,
+    pub kind: VariableKind,
+}
+
+/// Where a name came from, which decides whether it can be assigned to and where it's listed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariableKind {
+    /// Built-in constants like `pi`, which can only be replaced with `shadow`
+    Builtin,
+    /// Declared with `name = value`
+    User,
+    /// User-defined functions like `f(x) = x^2`, which are kept in [Variables::functions](crate::variables::Variables::functions)
+    Function,
+    /// `ans`, which always holds the last result
+    Result,
+}
+
+impl VariableKind {
+    /// Kinds in the order they're listed
+    pub const ALL: [VariableKind; 4] = [
+        VariableKind::Builtin,
+        VariableKind::User,
+        VariableKind::Function,
+        VariableKind::Result,
+    ];
+
+    /// Whether a plain `name = value` declaration can replace a name of this kind
+    pub fn is_assignable(&self) -> bool {
+        matches!(self, VariableKind::User | VariableKind::Function)
+    }
+}
+
+impl Display for VariableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            VariableKind::Builtin => "Built-in",
+            VariableKind::User => "User-defined",
+            VariableKind::Function => "Functions",
+            VariableKind::Result => "Results",
+        };
+
+        write!(f, "{name}")
+    }
 }
 
 impl Variable {
-    pub fn new<S: ToString>(key: S, value: f64, aliases: Option<Vec<String>>) -> Self {
+    pub fn new<S: ToString>(
+        key: S,
+        value: Value,
+        aliases: Option<Vec<String>>,
+        kind: VariableKind,
+    ) -> Self {
         Self {
             key: key.to_string().into_boxed_str(),
             value,
             aliases,
+            kind,
         }
     }
 
@@ -18,12 +70,14 @@ impl Variable {
         key: S,
         value: F,
         aliases: Option<Vec<String>>,
+        kind: VariableKind,
     ) -> Self {
         let fl: f64 = value.into();
         Self {
             key: key.to_string().into_boxed_str(),
-            value: fl,
+            value: Number::Float(fl).into(),
             aliases,
+            kind,
         }
     }
 
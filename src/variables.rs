@@ -1,15 +1,26 @@
-use core::f64;
 use std::io::{self, Write};
 
 use crossterm::{cursor, terminal, QueueableCommand};
 use linked_hash_map::LinkedHashMap;
 
-use crate::variable::Variable;
+use num_complex::Complex64;
+
+use crate::{
+    constants::CONSTANTS,
+    function::Function,
+    number::Number,
+    value::{DisplayUnit, Value},
+    variable::{Variable, VariableKind},
+};
+
+/// Name of the variable holding the last result
+pub const RESULT_NAME: &str = "ans";
 
 #[derive(Clone)]
 pub struct Variables {
     pub variables: LinkedHashMap<String, Variable>,
-    pub builtin_variable_count: usize,
+    /// User-defined functions, keyed by name
+    pub functions: LinkedHashMap<String, Function>,
 }
 
 impl IntoIterator for Variables {
@@ -27,75 +38,220 @@ impl Default for Variables {
         let mut variables = LinkedHashMap::<String, Variable>::new();
         Variables::add_basic_constants(&mut variables);
 
-        let builtin_variable_count = variables.len();
+        _ = io::stdout()
+            .queue(cursor::MoveUp(1))
+            .unwrap()
+            .queue(terminal::Clear(terminal::ClearType::CurrentLine));
+        io::stdout().flush().unwrap();
 
         Self {
             variables,
-            builtin_variable_count,
+            functions: LinkedHashMap::new(),
         }
     }
 }
 
 impl Variables {
     pub(crate) fn add_basic_constants(variables: &mut LinkedHashMap<String, Variable>) {
-        // Euler's number
-        variables.insert(
-            "e".to_owned(),
-            Variable::new_f64("e", f64::consts::E, Some(vec!["euler".to_string()])),
-        );
+        for constant in CONSTANTS {
+            let value = Value::new(Number::Float(constant.value), constant.dimension);
+            let display_unit = (!constant.unit.is_empty()).then(|| DisplayUnit {
+                name: constant.unit.to_owned(),
+                factor: Number::Integer(1.into()),
+                offset: Number::Integer(0.into()),
+            });
+            let aliases = constant.aliases.iter().map(|alias| alias.to_string());
 
-        // Euler's constant
-        variables.insert(
-            "γ".to_owned(),
-            Variable::new_f64("γ", f64::exp(1.0), Some(vec!["ℇ".to_string()])),
-        );
+            variables.insert(
+                constant.name.to_owned(),
+                Variable::new(
+                    constant.name,
+                    value.with_display_unit(display_unit),
+                    Some(aliases.collect()),
+                    VariableKind::Builtin,
+                ),
+            );
+        }
 
-        // Pi
+        // Imaginary unit
         variables.insert(
-            "pi".to_owned(),
-            Variable::new_f64("pi", f64::consts::PI, Some(vec!["π".to_string()])),
+            "i".to_owned(),
+            Variable::new(
+                "i",
+                Number::Complex(Complex64::i()).into(),
+                Some(vec!["j".to_string()]),
+                VariableKind::Builtin,
+            ),
         );
+    }
 
-        _ = io::stdout()
-            .queue(cursor::MoveUp(1))
-            .unwrap()
-            .queue(terminal::Clear(terminal::ClearType::CurrentLine));
-        io::stdout().flush().unwrap();
+    /// Removes all user-defined variables, functions, and results, restoring any built-ins that were shadowed
+    pub fn clear(&mut self) {
+        self.variables.clear();
+        Variables::add_basic_constants(&mut self.variables);
+        self.functions.clear();
     }
 
-    pub fn add(&mut self, variable: Variable) {
+    /// Adds a variable, replacing any existing variable with the same name
+    ///
+    /// A replaced variable's aliases are kept, so shadowing `pi` also shadows `π`
+    pub fn add(&mut self, mut variable: Variable) {
         let value = self.variables.get_mut(&variable.key.to_string());
         if let Some(value) = value {
+            if variable.aliases.is_none() {
+                variable.aliases = value.aliases.take();
+            }
+
             *value = variable;
         } else {
             self.variables.insert(variable.key.to_string(), variable);
         }
     }
 
+    /// Finds a variable by name or alias
+    ///
+    /// Names are checked before aliases, so a variable shadowing an alias like `π` is found instead of the built-in
     pub fn get<S: ToString>(&self, key: S) -> Option<Variable> {
         let key = key.to_string();
-        for (k, v) in self.variables.iter() {
-            if *k == key {
-                return Some(v.clone());
+        if let Some(variable) = self.variables.get(&key) {
+            return Some(variable.clone());
+        }
+
+        self.variables
+            .values()
+            .find(|variable| {
+                variable
+                    .aliases
+                    .as_ref()
+                    .is_some_and(|aliases| aliases.contains(&key))
+            })
+            .cloned()
+    }
+
+    /// Kind of whatever `name` refers to, checking variables before functions
+    ///
+    /// [RESULT_NAME] is always a [VariableKind::Result], even before anything has been calculated
+    pub fn kind<S: ToString>(&self, name: S) -> Option<VariableKind> {
+        let name = name.to_string();
+        if name == RESULT_NAME {
+            return Some(VariableKind::Result);
+        }
+
+        match self.get(&name) {
+            Some(variable) => Some(variable.kind),
+            None => self
+                .functions
+                .contains_key(&name)
+                .then_some(VariableKind::Function),
+        }
+    }
+
+    /// Stores the last result in [RESULT_NAME]
+    pub fn set_result(&mut self, value: Value) {
+        self.add(Variable::new(
+            RESULT_NAME,
+            value,
+            None,
+            VariableKind::Result,
+        ));
+    }
+
+    /// Adds a function, replacing any existing function with the same name
+    pub fn add_function(&mut self, function: Function) {
+        self.functions.insert(function.name.to_string(), function);
+    }
+
+    pub fn get_function<S: ToString>(&self, name: S) -> Option<&Function> {
+        self.functions.get(&name.to_string())
+    }
+
+    /// Removes user-defined variables and functions whose name or alias matches `pattern`, which can be a [glob](glob_match) like `tmp*`
+    ///
+    /// Built-ins and `ans` are never removed, but removing a variable that shadowed a built-in brings the built-in back.
+    /// Returns what was removed, with functions shown by their signature
+    pub fn remove(&mut self, pattern: &str) -> Vec<String> {
+        let matching_variables: Vec<String> = self
+            .variables
+            .iter()
+            .filter(|(_, variable)| variable.kind == VariableKind::User)
+            .filter(|(key, variable)| {
+                glob_match(pattern, key)
+                    || variable
+                        .aliases
+                        .iter()
+                        .flatten()
+                        .any(|alias| glob_match(pattern, alias))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let matching_functions: Vec<String> = self
+            .functions
+            .keys()
+            .filter(|name| glob_match(pattern, name))
+            .cloned()
+            .collect();
+
+        let mut builtins = LinkedHashMap::new();
+        Variables::add_basic_constants(&mut builtins);
+
+        let mut removed = Vec::new();
+        for key in matching_variables {
+            match builtins.remove(&key) {
+                // Replaced in place so the built-in keeps its spot in the list
+                Some(builtin) => self.variables[&key] = builtin,
+                None => _ = self.variables.remove(&key),
             }
 
-            if let Some(aliases) = &v.aliases {
-                if aliases.contains(&key) {
-                    return Some(v.clone());
-                }
+            removed.push(key);
+        }
+
+        for name in matching_functions {
+            if let Some(function) = self.functions.remove(&name) {
+                removed.push(function.signature());
             }
         }
 
-        None
+        removed
     }
+}
 
-    pub fn remove<S: ToString>(mut self, key: S) {
-        let key = key.to_string();
-        for (k, v) in self.variables.clone().iter() {
-            if *k == key || (v.aliases.is_some() && v.aliases.as_ref().unwrap().contains(k)) {
-                self.variables.remove(k);
-                return;
+/// Whether `name` matches `pattern`, where `*` matches any number of characters and `?` matches exactly one
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some('*'), _) => {
+                matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
             }
+            (Some('?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+            _ => false,
         }
     }
+
+    matches(&pattern, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("x", "x"));
+        assert!(!glob_match("x", "xy"));
+        assert!(glob_match("x*", "x"));
+        assert!(glob_match("x*", "xyz"));
+        assert!(glob_match("*z", "xyz"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("x?z", "xyz"));
+        assert!(!glob_match("x?z", "xz"));
+        assert!(glob_match("?π", "2π"));
+        assert!(glob_match("*_*", "total_2"));
+        assert!(!glob_match("*_*", "total"));
+    }
 }
@@ -0,0 +1,231 @@
+//! Saving user-defined variables and functions between sessions
+use std::{fs, path::PathBuf, str::FromStr};
+
+use inline_colorization::*;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    ast,
+    function::Function,
+    lexer,
+    number::Number,
+    unit::Dimension,
+    value::{DisplayUnit, Value},
+    variable::{Variable, VariableKind},
+    CONFIG, VARIABLES,
+};
+
+/// Contents of the workspace file
+#[derive(Deserialize, Serialize, Default)]
+struct Workspace {
+    #[serde(default)]
+    variables: Vec<SavedVariable>,
+    #[serde(default)]
+    functions: Vec<SavedFunction>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SavedVariable {
+    name: String,
+    /// Value in SI base units, see [encode_number]
+    value: String,
+    #[serde(default, skip_serializing_if = "Dimension::is_none")]
+    dimension: Dimension,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unit: Option<SavedUnit>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SavedUnit {
+    name: String,
+    factor: String,
+    offset: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SavedFunction {
+    name: String,
+    parameters: Vec<String>,
+    body: String,
+}
+
+/// Path of the workspace file, or [None] if saving the workspace is turned off in [CONFIG]
+fn path() -> Option<PathBuf> {
+    let config = CONFIG.lock().unwrap();
+    let config = config.as_ref()?;
+
+    config
+        .save_workspace
+        .then(|| PathBuf::from_str(&config.workspace_file).unwrap())
+}
+
+/// Saves the workspace to the configured workspace file on the way out, unless saving it is turned off
+///
+/// Unlike the `save` command, which returns the error, a failed write here is only printed since the calculator is exiting anyway
+pub fn save_on_exit() {
+    let Some(path) = path() else {
+        return;
+    };
+
+    if let Err(err) = save(&path) {
+        println!(
+            "{color_red}Couldn't save the workspace to {}: {err}{color_reset}",
+            path.display()
+        );
+    }
+}
+
+/// Writes user-defined variables and functions to `path`, returning how many of each were saved
+pub fn save(path: &PathBuf) -> std::io::Result<(usize, usize)> {
+    let variables = VARIABLES.lock().unwrap();
+
+    let workspace = Workspace {
+        variables: variables
+            .variables
+            .values()
+            .filter(|variable| variable.kind == VariableKind::User)
+            .map(|variable| SavedVariable {
+                name: variable.key.to_string(),
+                value: encode_number(&variable.value.number),
+                dimension: variable.value.dimension,
+                unit: variable.value.display_unit.as_ref().map(|unit| SavedUnit {
+                    name: unit.name.clone(),
+                    factor: encode_number(&unit.factor),
+                    offset: encode_number(&unit.offset),
+                }),
+            })
+            .collect(),
+        functions: variables
+            .functions
+            .values()
+            .map(|function| SavedFunction {
+                name: function.name.to_string(),
+                parameters: function.parameters.clone(),
+                body: function.source.clone(),
+            })
+            .collect(),
+    };
+
+    drop(variables);
+
+    let contents = toml::to_string(&workspace).map_err(std::io::Error::other)?;
+    fs::write(path, contents)?;
+
+    Ok((workspace.variables.len(), workspace.functions.len()))
+}
+
+/// Reads variables and functions saved in a previous session, if the workspace file is turned on and exists
+///
+/// Anything that can't be read is skipped with a warning, so one bad entry doesn't lose the rest
+pub fn load() {
+    let Some(path) = path() else {
+        return;
+    };
+
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return;
+    };
+
+    let Ok(workspace) = toml::from_str::<Workspace>(&raw) else {
+        println!(
+            "{color_red}Workspace file {} is invalid, starting with an empty workspace{color_reset}",
+            path.display()
+        );
+        return;
+    };
+
+    let mut variables = VARIABLES.lock().unwrap();
+
+    for saved in workspace.variables {
+        let Some(value) = decode_value(&saved) else {
+            println!(
+                "{color_red}Couldn't load variable {} from the workspace{color_reset}",
+                saved.name
+            );
+            continue;
+        };
+
+        variables.add(Variable::new(saved.name, value, None, VariableKind::User));
+    }
+
+    for saved in workspace.functions {
+        let body = lexer::tokenize(&saved.body).and_then(|tokens| ast::build(&tokens));
+        let Ok(body) = body else {
+            println!(
+                "{color_red}Couldn't load function {} from the workspace{color_reset}",
+                saved.name
+            );
+            continue;
+        };
+
+        variables.add_function(Function::new(
+            saved.name,
+            saved.parameters,
+            body,
+            saved.body,
+        ));
+    }
+}
+
+fn decode_value(saved: &SavedVariable) -> Option<Value> {
+    let display_unit = match &saved.unit {
+        Some(unit) => Some(DisplayUnit {
+            name: unit.name.clone(),
+            factor: decode_number(&unit.factor)?,
+            offset: decode_number(&unit.offset)?,
+        }),
+        None => None,
+    };
+
+    let number = decode_number(&saved.value)?;
+    Some(Value::new(number, saved.dimension).with_display_unit(display_unit))
+}
+
+/// Writes a number so it can be read back exactly: integers like `4`, fractions like `7/4`, floats like `0.1` or `4.0`, and complex numbers like `3.0+4.0i`
+fn encode_number(number: &Number) -> String {
+    match number {
+        Number::Integer(value) => value.to_string(),
+        Number::Rational(value) => format!("{}/{}", value.numer(), value.denom()),
+        // Debug formatting always has a decimal point or exponent, which keeps floats apart from integers
+        Number::Float(value) => format!("{value:?}"),
+        Number::Complex(value) => format!("{:?}{:+?}i", value.re, value.im),
+    }
+}
+
+/// Reads a number written by [encode_number]
+fn decode_number(text: &str) -> Option<Number> {
+    if let Some(complex) = text.strip_suffix('i') {
+        // The imaginary part starts at the last sign that isn't part of an exponent or the real part's sign
+        let split = complex
+            .char_indices()
+            .skip(1)
+            .filter(|(i, c)| matches!(c, '+' | '-') && !complex[..*i].ends_with(['e', 'E']))
+            .map(|(i, _)| i)
+            .last()?;
+
+        let re = complex[..split].parse().ok()?;
+        let im = complex[split..].parse().ok()?;
+        return Some(Number::Complex(Complex64::new(re, im)));
+    }
+
+    if let Some((numerator, denominator)) = text.split_once('/') {
+        let numerator: BigInt = numerator.parse().ok()?;
+        let denominator: BigInt = denominator.parse().ok()?;
+        if denominator == BigInt::from(0) {
+            return None;
+        }
+
+        return Some(Number::from_rational(BigRational::new(
+            numerator,
+            denominator,
+        )));
+    }
+
+    match text.parse::<BigInt>() {
+        Ok(value) => Some(Number::Integer(value)),
+        Err(_) => text.parse().ok().map(Number::Float),
+    }
+}